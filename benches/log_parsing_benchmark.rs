@@ -1,24 +1,37 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use log_parser::{LogEntry, LogLevel};
+use log_parser::filters::LevelFilter;
+use log_parser::parsers::TextParser;
+use log_parser::{Filter, LogEntry, LogLevel, Parser};
 
 fn benchmark_log_parsing(c: &mut Criterion) {
+    let parser = TextParser::new().unwrap();
+
     c.bench_function("parse single log entry", |b| {
         let log_line = "2024-01-01 12:00:00 [ERROR] Database connection failed";
-        b.iter(|| {
-            // Placeholder for actual parsing logic
-            black_box(log_line)
-        });
+        b.iter(|| black_box(parser.parse_line(black_box(log_line)).unwrap()));
     });
 }
 
 fn benchmark_filtering(c: &mut Criterion) {
-    let entries: Vec<LogEntry> = vec![
-        // Placeholder entries - will be implemented in foundation phase
+    let levels = [
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
     ];
+    let entries: Vec<LogEntry> = (0..1000)
+        .map(|i| LogEntry::new(format!("entry {}", i)).with_level(levels[i % levels.len()]))
+        .collect();
+    let filter = LevelFilter::new(LogLevel::Error);
 
     c.bench_function("filter 1000 entries", |b| {
         b.iter(|| {
-            black_box(&entries);
+            black_box(
+                entries
+                    .iter()
+                    .filter(|entry| filter.apply(entry).unwrap())
+                    .count(),
+            )
         });
     });
 }
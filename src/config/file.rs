@@ -0,0 +1,53 @@
+use crate::config::FormatDefinition;
+use crate::core::{LogParserError, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Contents of the TOML config file (`~/.config/log-parser/config.toml` on Linux).
+///
+/// ```toml
+/// input_format = "myapp"
+/// timezone = "Asia/Tokyo"
+/// log_line_prefix = "%t [%p]: user=%u,db=%d "
+///
+/// [formats.myapp]
+/// pattern = "%{TIMESTAMP} [%{LEVEL}] %{WORD:module}: %{MSG}"
+/// levels = { E = "error", W = "warn" }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    pub input_format: Option<String>,
+    /// Timezone of timestamps without zone information (`UTC`, `local`, `+09:00`, `Asia/Tokyo`)
+    pub timezone: Option<String>,
+    /// `log_line_prefix` of the PostgreSQL server whose logs are read
+    pub log_line_prefix: Option<String>,
+    /// Grok pattern files (or directories of them) loaded in addition to the built-in library
+    pub grok_pattern_files: Vec<PathBuf>,
+    pub formats: BTreeMap<String, FormatDefinition>,
+}
+
+impl ConfigFile {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+
+        toml::from_str(&content).map_err(|e| LogParserError::Config {
+            message: format!("{}: {}", path.display(), e),
+        })
+    }
+
+    /// Load the file at the platform config location, or an empty config if there is none.
+    pub fn load_default() -> Result<Self> {
+        match Self::default_path() {
+            Some(path) if path.exists() => Self::load(path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn default_path() -> Option<PathBuf> {
+        directories::ProjectDirs::from("", "", "log-parser")
+            .map(|dirs| dirs.config_dir().join("config.toml"))
+    }
+}
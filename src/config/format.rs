@@ -0,0 +1,21 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// A user-defined log format from the `[formats.<name>]` section of the config file.
+///
+/// Exactly one of `pattern` (placeholder syntax such as
+/// `%{TIMESTAMP} [%{LEVEL}] %{WORD:module}: %{MSG}`), `regex` (named capture groups) or
+/// `grok` (a grok expression such as `%{COMBINEDAPACHELOG}`) must be given. Captures named
+/// `timestamp`, `level` and `message` fill the corresponding `LogEntry` members; every other
+/// capture becomes a field.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FormatDefinition {
+    pub pattern: Option<String>,
+    pub regex: Option<String>,
+    pub grok: Option<String>,
+    /// chrono format string for the `timestamp` capture, e.g. `%d/%m/%Y %H:%M:%S`
+    pub timestamp_format: Option<String>,
+    /// Maps raw level strings (case-insensitive) onto level names, e.g. `E = "error"`
+    pub levels: BTreeMap<String, String>,
+}
@@ -1,29 +1,93 @@
+mod file;
+mod format;
+
+pub use file::ConfigFile;
+pub use format::FormatDefinition;
+
+use crate::core::SourceTimezone;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone)]
 pub struct Config {
-    pub file_path: PathBuf,
+    /// Files, directories and glob patterns to read
+    pub file_paths: Vec<PathBuf>,
+    /// Descend into subdirectories of directory inputs
+    pub recursive: bool,
+    /// Globs selecting the files read from directories and patterns
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    /// Also read the rotated predecessors of each file (`app.log.1`, `app.log.2.gz`, ...)
+    pub rotated: bool,
+    /// Interleave the entries of all inputs by timestamp instead of reading them in turn
+    pub merge: bool,
+    /// Prefix text output with the source file; by default only for several files
+    pub with_filename: Option<bool>,
     pub level_filter: Option<String>,
+    /// Match `level_filter` exactly instead of as a minimum
+    pub level_exact: bool,
     pub since: Option<String>,
     pub until: Option<String>,
     pub grep_pattern: Option<String>,
+    pub field_filters: Vec<String>,
+    pub input_format: String,
+    /// Format of the application lines inside docker/cri container logs
+    pub inner_format: Option<String>,
+    /// PostgreSQL `log_line_prefix` of the server that wrote the log
+    pub log_line_prefix: Option<String>,
+    pub detect_sample_lines: usize,
+    pub multiline: bool,
+    pub multiline_start: Option<String>,
+    pub multiline_continue: Vec<String>,
+    pub timestamp_key: Option<String>,
+    pub level_key: Option<String>,
+    pub message_key: Option<String>,
+    /// Timezone of timestamps that carry no zone of their own
+    pub timezone: SourceTimezone,
+    pub formats: BTreeMap<String, FormatDefinition>,
+    pub grok_pattern: Option<String>,
+    pub grok_pattern_files: Vec<PathBuf>,
     pub output_format: String,
     pub follow: bool,
     pub show_stats: bool,
+    pub show_fields: bool,
     pub tui_mode: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
-            file_path: PathBuf::new(),
+            file_paths: Vec::new(),
+            recursive: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            rotated: false,
+            merge: false,
+            with_filename: None,
             level_filter: None,
+            level_exact: false,
             since: None,
             until: None,
             grep_pattern: None,
+            field_filters: Vec::new(),
+            input_format: "text".to_string(),
+            inner_format: None,
+            log_line_prefix: None,
+            detect_sample_lines: 100,
+            multiline: false,
+            multiline_start: None,
+            multiline_continue: Vec::new(),
+            timestamp_key: None,
+            level_key: None,
+            message_key: None,
+            timezone: SourceTimezone::Utc,
+            formats: BTreeMap::new(),
+            grok_pattern: None,
+            grok_pattern_files: Vec::new(),
             output_format: "text".to_string(),
             follow: false,
             show_stats: false,
+            show_fields: false,
             tui_mode: false,
         }
     }
@@ -0,0 +1,54 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+/// Compression formats recognized from the first bytes of an input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Bzip2,
+    Xz,
+    Zstd,
+}
+
+impl Compression {
+    /// Identify the compression of a stream from its magic bytes, ignoring file names.
+    pub fn detect(header: &[u8]) -> Option<Self> {
+        if header.starts_with(&[0x1f, 0x8b]) {
+            Some(Compression::Gzip)
+        } else if header.starts_with(b"BZh") {
+            Some(Compression::Bzip2)
+        } else if header.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Some(Compression::Xz)
+        } else if header.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Compression::Zstd)
+        } else {
+            None
+        }
+    }
+}
+
+/// Open a log file for reading, decompressing gzip, bzip2, xz and zstd input on the fly.
+pub fn open_input<P: AsRef<Path>>(path: P) -> io::Result<Box<dyn BufRead>> {
+    decompress(BufReader::new(File::open(path)?))
+}
+
+/// Wrap a reader so that compressed input is decompressed; other input is passed through.
+pub fn decompress<R: BufRead + 'static>(mut reader: R) -> io::Result<Box<dyn BufRead>> {
+    // Peek at the magic bytes without consuming them
+    let compression = Compression::detect(reader.fill_buf()?);
+
+    // Concatenated members/streams (`cat a.gz b.gz`, pigz, pbzip2) are read through to the end
+    let reader: Box<dyn BufRead> = match compression {
+        None => Box::new(reader),
+        Some(Compression::Gzip) => buffered(flate2::bufread::MultiGzDecoder::new(reader)),
+        Some(Compression::Bzip2) => buffered(bzip2::bufread::MultiBzDecoder::new(reader)),
+        Some(Compression::Xz) => buffered(xz2::bufread::XzDecoder::new_multi_decoder(reader)),
+        Some(Compression::Zstd) => buffered(zstd::stream::read::Decoder::with_buffer(reader)?),
+    };
+    Ok(reader)
+}
+
+fn buffered<R: Read + 'static>(reader: R) -> Box<dyn BufRead> {
+    Box::new(BufReader::new(reader))
+}
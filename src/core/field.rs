@@ -0,0 +1,223 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// Structured fields attached to a `LogEntry`, ordered by key for stable output.
+pub type Fields = BTreeMap<String, FieldValue>;
+
+/// A typed value extracted from a log line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    String(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Ip(IpAddr),
+    Duration(Duration),
+    Timestamp(DateTime<Utc>),
+}
+
+impl FieldValue {
+    /// Guess the type of an untyped text value (bool, integer, float, IP address,
+    /// duration with a unit such as `1.2s` or `250ms`, otherwise string).
+    pub fn infer(value: &str) -> Self {
+        if let Ok(b) = value.parse::<bool>() {
+            return FieldValue::Bool(b);
+        }
+        if let Ok(i) = value.parse::<i64>() {
+            return FieldValue::Int(i);
+        }
+        if let Ok(f) = value.parse::<f64>() {
+            // Reject "inf", "nan" and friends, which are almost always plain words in logs
+            if f.is_finite() {
+                return FieldValue::Float(f);
+            }
+        }
+        if let Ok(ip) = value.parse::<IpAddr>() {
+            return FieldValue::Ip(ip);
+        }
+        if value.starts_with(|c: char| c.is_ascii_digit()) {
+            if let Some(duration) = parse_duration(value) {
+                return FieldValue::Duration(duration);
+            }
+        }
+        FieldValue::String(value.to_string())
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            FieldValue::String(_) => "string",
+            FieldValue::Int(_) => "int",
+            FieldValue::Float(_) => "float",
+            FieldValue::Bool(_) => "bool",
+            FieldValue::Ip(_) => "ip",
+            FieldValue::Duration(_) => "duration",
+            FieldValue::Timestamp(_) => "timestamp",
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            FieldValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            FieldValue::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            FieldValue::Int(i) => Some(*i as f64),
+            FieldValue::Float(f) => Some(*f),
+            FieldValue::Duration(d) => Some(d.as_secs_f64()),
+            _ => None,
+        }
+    }
+
+    /// Compare this value with a textual operand, interpreting the operand with this value's type.
+    pub fn compare_str(&self, operand: &str) -> Option<Ordering> {
+        match self {
+            FieldValue::String(s) => Some(s.as_str().cmp(operand)),
+            FieldValue::Int(i) => match operand.parse::<i64>() {
+                Ok(other) => Some(i.cmp(&other)),
+                Err(_) => (*i as f64).partial_cmp(&operand.parse::<f64>().ok()?),
+            },
+            FieldValue::Float(f) => f.partial_cmp(&operand.parse::<f64>().ok()?),
+            FieldValue::Bool(b) => Some(b.cmp(&operand.parse::<bool>().ok()?)),
+            FieldValue::Ip(ip) => Some(ip.cmp(&operand.parse::<IpAddr>().ok()?)),
+            FieldValue::Duration(d) => Some(d.cmp(&parse_duration(operand)?)),
+            FieldValue::Timestamp(ts) => {
+                let other = DateTime::parse_from_rfc3339(operand).ok()?;
+                Some(ts.cmp(&other.with_timezone(&Utc)))
+            }
+        }
+    }
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::String(s) => write!(f, "{}", s),
+            FieldValue::Int(i) => write!(f, "{}", i),
+            FieldValue::Float(v) => write!(f, "{}", v),
+            FieldValue::Bool(b) => write!(f, "{}", b),
+            FieldValue::Ip(ip) => write!(f, "{}", ip),
+            FieldValue::Duration(d) => write!(f, "{}s", d.as_secs_f64()),
+            FieldValue::Timestamp(ts) => write!(f, "{}", ts.to_rfc3339()),
+        }
+    }
+}
+
+impl Serialize for FieldValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            FieldValue::String(s) => serializer.serialize_str(s),
+            FieldValue::Int(i) => serializer.serialize_i64(*i),
+            FieldValue::Float(f) => serializer.serialize_f64(*f),
+            FieldValue::Bool(b) => serializer.serialize_bool(*b),
+            // Durations are written as (fractional) seconds
+            FieldValue::Duration(d) => serializer.serialize_f64(d.as_secs_f64()),
+            FieldValue::Ip(_) | FieldValue::Timestamp(_) => serializer.collect_str(self),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for FieldValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Bool(bool),
+            Int(i64),
+            Float(f64),
+            String(String),
+        }
+
+        Ok(match Raw::deserialize(deserializer)? {
+            Raw::Bool(b) => FieldValue::Bool(b),
+            Raw::Int(i) => FieldValue::Int(i),
+            Raw::Float(f) => FieldValue::Float(f),
+            Raw::String(s) => FieldValue::String(s),
+        })
+    }
+}
+
+impl From<&str> for FieldValue {
+    fn from(value: &str) -> Self {
+        FieldValue::String(value.to_string())
+    }
+}
+
+impl From<String> for FieldValue {
+    fn from(value: String) -> Self {
+        FieldValue::String(value)
+    }
+}
+
+impl From<i64> for FieldValue {
+    fn from(value: i64) -> Self {
+        FieldValue::Int(value)
+    }
+}
+
+impl From<f64> for FieldValue {
+    fn from(value: f64) -> Self {
+        FieldValue::Float(value)
+    }
+}
+
+impl From<bool> for FieldValue {
+    fn from(value: bool) -> Self {
+        FieldValue::Bool(value)
+    }
+}
+
+impl From<IpAddr> for FieldValue {
+    fn from(value: IpAddr) -> Self {
+        FieldValue::Ip(value)
+    }
+}
+
+impl From<Duration> for FieldValue {
+    fn from(value: Duration) -> Self {
+        FieldValue::Duration(value)
+    }
+}
+
+impl From<DateTime<Utc>> for FieldValue {
+    fn from(value: DateTime<Utc>) -> Self {
+        FieldValue::Timestamp(value)
+    }
+}
+
+/// Parse a duration such as `1.5s`, `250ms`, `30us`, `100ns`, `2m` or `1h`.
+/// A bare number is interpreted as seconds.
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+
+    let seconds = match unit {
+        "" | "s" | "sec" => number,
+        "ms" => number / 1_000.0,
+        "us" | "µs" => number / 1_000_000.0,
+        "ns" => number / 1_000_000_000.0,
+        "m" | "min" => number * 60.0,
+        "h" => number * 3_600.0,
+        _ => return None,
+    };
+
+    Duration::try_from_secs_f64(seconds).ok()
+}
@@ -1,25 +1,95 @@
+use crate::core::field::{FieldValue, Fields};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Log severity, ordered from least to most severe so that `level >= LogLevel::Warn`
+/// selects warnings and everything worse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum LogLevel {
-    Error,
-    Warn,
-    Info,
+    Trace,
     Debug,
+    Info,
+    Notice,
+    Warn,
+    Error,
+    Critical,
+    Fatal,
+    Alert,
+    Emergency,
+}
+
+impl LogLevel {
+    /// Map a syslog severity (0 = emerg ... 7 = debug).
+    pub fn from_syslog_severity(severity: u8) -> Option<Self> {
+        let level = match severity {
+            0 => LogLevel::Emergency,
+            1 => LogLevel::Alert,
+            2 => LogLevel::Critical,
+            3 => LogLevel::Error,
+            4 => LogLevel::Warn,
+            5 => LogLevel::Notice,
+            6 => LogLevel::Info,
+            7 => LogLevel::Debug,
+            _ => return None,
+        };
+        Some(level)
+    }
+
+    /// The syslog severity of this level; levels syslog lacks map to the nearest one.
+    pub fn syslog_severity(self) -> u8 {
+        match self {
+            LogLevel::Emergency => 0,
+            LogLevel::Alert => 1,
+            LogLevel::Critical | LogLevel::Fatal => 2,
+            LogLevel::Error => 3,
+            LogLevel::Warn => 4,
+            LogLevel::Notice => 5,
+            LogLevel::Info => 6,
+            LogLevel::Debug | LogLevel::Trace => 7,
+        }
+    }
+
+    /// Map a bunyan/pino numeric level (10 = trace ... 60 = fatal).
+    pub fn from_bunyan(level: u8) -> Option<Self> {
+        let level = match level {
+            10 => LogLevel::Trace,
+            20 => LogLevel::Debug,
+            30 => LogLevel::Info,
+            40 => LogLevel::Warn,
+            50 => LogLevel::Error,
+            60 => LogLevel::Fatal,
+            _ => return None,
+        };
+        Some(level)
+    }
 }
 
 impl std::str::FromStr for LogLevel {
     type Err = String;
 
+    /// Accepts level names and common abbreviations (`E`, `ERR`, `WARNING`, `crit`, ...),
+    /// syslog severities `0`-`7` and bunyan levels `10`-`60`.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "error" => Ok(LogLevel::Error),
-            "warn" | "warning" => Ok(LogLevel::Warn),
-            "info" => Ok(LogLevel::Info),
-            "debug" => Ok(LogLevel::Debug),
-            _ => Err(format!("Invalid log level: {}", s)),
-        }
+        let level = match s.trim().to_lowercase().as_str() {
+            "trace" | "trc" | "t" | "verbose" | "v" | "finest" | "finer" => LogLevel::Trace,
+            "debug" | "dbg" | "d" | "fine" => LogLevel::Debug,
+            "info" | "inf" | "i" | "information" | "informational" => LogLevel::Info,
+            "notice" | "n" => LogLevel::Notice,
+            "warn" | "warning" | "wrn" | "w" => LogLevel::Warn,
+            "error" | "err" | "e" | "severe" => LogLevel::Error,
+            "critical" | "crit" | "c" => LogLevel::Critical,
+            "fatal" | "ftl" | "f" | "panic" => LogLevel::Fatal,
+            "alert" | "a" => LogLevel::Alert,
+            "emergency" | "emerg" => LogLevel::Emergency,
+            number => number
+                .parse::<u8>()
+                .ok()
+                .and_then(|n| {
+                    LogLevel::from_syslog_severity(n).or_else(|| LogLevel::from_bunyan(n))
+                })
+                .ok_or_else(|| format!("Invalid log level: {}", s))?,
+        };
+        Ok(level)
     }
 }
 
@@ -29,6 +99,12 @@ pub struct LogEntry {
     pub level: Option<LogLevel>,
     pub message: String,
     pub raw_line: String,
+    /// Additional values extracted by the parser (client address, status code, ...)
+    #[serde(default, skip_serializing_if = "Fields::is_empty")]
+    pub fields: Fields,
+    /// Path of the file the entry was read from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
 }
 
 impl LogEntry {
@@ -38,6 +114,8 @@ impl LogEntry {
             level: None,
             message: raw_line.clone(),
             raw_line,
+            fields: Fields::new(),
+            source: None,
         }
     }
 
@@ -55,4 +133,18 @@ impl LogEntry {
         self.message = message;
         self
     }
+
+    pub fn with_field(mut self, key: impl Into<String>, value: impl Into<FieldValue>) -> Self {
+        self.fields.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    pub fn field(&self, key: &str) -> Option<&FieldValue> {
+        self.fields.get(key)
+    }
 }
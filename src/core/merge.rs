@@ -0,0 +1,105 @@
+use crate::core::{LogEntry, Result};
+use chrono::{DateTime, Utc};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+/// Streaming k-way merge of several entry sources, each already in its own order, by timestamp.
+///
+/// Only the next entry of each source is held, so memory does not grow with the input.
+/// Entries with equal timestamps keep the order of their sources. Undated entries (continuation
+/// lines, ...) follow the entry before them in their source; undated entries at the start of a
+/// source come before all dated ones.
+pub struct MergedEntries<I> {
+    sources: Vec<I>,
+    heap: BinaryHeap<Reverse<Head>>,
+    // Source whose last entry was returned, continued while its entries are undated
+    active: Option<usize>,
+    started: bool,
+}
+
+// Next entry of one source, ordered by timestamp and then by source
+struct Head {
+    timestamp: Option<DateTime<Utc>>,
+    source: usize,
+    entry: LogEntry,
+}
+
+impl Head {
+    fn key(&self) -> (Option<DateTime<Utc>>, usize) {
+        (self.timestamp, self.source)
+    }
+}
+
+impl PartialEq for Head {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Head {}
+
+impl PartialOrd for Head {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Head {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl<I: Iterator<Item = Result<LogEntry>>> MergedEntries<I> {
+    pub fn new(sources: Vec<I>) -> Self {
+        let capacity = sources.len();
+        Self {
+            sources,
+            heap: BinaryHeap::with_capacity(capacity),
+            active: None,
+            started: false,
+        }
+    }
+
+    fn push(&mut self, source: usize, entry: LogEntry) {
+        self.heap.push(Reverse(Head {
+            timestamp: entry.timestamp,
+            source,
+            entry,
+        }));
+    }
+}
+
+impl<I: Iterator<Item = Result<LogEntry>>> Iterator for MergedEntries<I> {
+    type Item = Result<LogEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+            for source in 0..self.sources.len() {
+                match self.sources[source].next() {
+                    Some(Ok(entry)) => self.push(source, entry),
+                    Some(Err(e)) => return Some(Err(e)),
+                    None => {}
+                }
+            }
+        }
+
+        // Undated entries stay with the entry before them; the next dated one waits its turn
+        if let Some(source) = self.active.take() {
+            match self.sources[source].next() {
+                Some(Ok(entry)) if entry.timestamp.is_none() => {
+                    self.active = Some(source);
+                    return Some(Ok(entry));
+                }
+                Some(Ok(entry)) => self.push(source, entry),
+                Some(Err(e)) => return Some(Err(e)),
+                None => {}
+            }
+        }
+
+        let Reverse(head) = self.heap.pop()?;
+        self.active = Some(head.source);
+        Some(Ok(head.entry))
+    }
+}
@@ -1,7 +1,21 @@
+mod compression;
 mod error;
+mod field;
 mod log_entry;
+mod merge;
+mod multiline;
+mod rotation;
+mod sources;
 mod stream;
+mod timestamp;
 
+pub use compression::{decompress, open_input, Compression};
 pub use error::{LogParserError, Result};
+pub use field::{parse_duration, FieldValue, Fields};
 pub use log_entry::{LogEntry, LogLevel};
-pub use stream::StreamProcessor;
+pub use merge::MergedEntries;
+pub use multiline::{MultilineAssembler, MultilineLines};
+pub use rotation::{rotated_set, rotated_sets};
+pub use sources::InputSelector;
+pub use stream::{BasicStreamProcessor, StreamProcessor};
+pub use timestamp::{parse_epoch, SourceTimezone, TimestampParser};
@@ -0,0 +1,132 @@
+use crate::core::Result;
+use regex::Regex;
+
+// Continuation rules used when none are configured: indented lines (stack frames),
+// chained Java causes, Python tracebacks and the exception line that ends them
+const DEFAULT_CONTINUATION_RULES: &[&str] = &[
+    r"^\s+\S",
+    r"^Caused by:",
+    r"^Traceback \(most recent call last\):",
+    r"^During handling of the above exception",
+    r"^The above exception was the direct cause",
+    r"^\.\.\. \d+ (?:more|common frames omitted)",
+    r"^(?:[\w$]+\.)*[\w$]*(?:Exception|Error)(?::|$)",
+];
+
+// Upper bound on physical lines per entry so a missing start line cannot grow one entry forever
+const MAX_LINES_PER_ENTRY: usize = 10_000;
+
+/// Joins physical lines into logical log entries (stack traces, tracebacks, ...).
+///
+/// With a start rule, every line that does not match it is attached to the previous entry.
+/// Lines matching a continuation rule are always attached, start rule or not. Lines come
+/// with a number and every entry keeps the number of its first line.
+pub struct MultilineAssembler {
+    start: Option<Regex>,
+    continuation: Vec<Regex>,
+    pending: Option<(usize, String)>,
+    pending_lines: usize,
+}
+
+impl MultilineAssembler {
+    pub fn new(start: Option<&str>, continuation: &[String]) -> Result<Self> {
+        let start = start.map(Regex::new).transpose()?;
+        let continuation = if continuation.is_empty() {
+            DEFAULT_CONTINUATION_RULES
+                .iter()
+                .map(|rule| Regex::new(rule))
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        } else {
+            continuation
+                .iter()
+                .map(|rule| Regex::new(rule))
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        };
+
+        Ok(Self {
+            start,
+            continuation,
+            pending: None,
+            pending_lines: 0,
+        })
+    }
+
+    /// Feed one physical line; returns the previous entry once this line starts a new one.
+    pub fn push(&mut self, number: usize, line: String) -> Option<(usize, String)> {
+        let attach = self.pending_lines < MAX_LINES_PER_ENTRY && self.is_continuation(&line);
+        if let Some((_, pending)) = self.pending.as_mut().filter(|_| attach) {
+            pending.push('\n');
+            pending.push_str(&line);
+            self.pending_lines += 1;
+            return None;
+        }
+
+        self.pending_lines = 1;
+        self.pending.replace((number, line))
+    }
+
+    /// Return the last buffered entry at end of input.
+    pub fn finish(&mut self) -> Option<(usize, String)> {
+        self.pending_lines = 0;
+        self.pending.take()
+    }
+
+    /// Adapt an iterator of numbered lines into an iterator of assembled entries.
+    pub fn assemble<I>(self, lines: I) -> MultilineLines<I>
+    where
+        I: Iterator<Item = std::io::Result<(usize, String)>>,
+    {
+        MultilineLines {
+            assembler: self,
+            lines,
+            done: false,
+        }
+    }
+
+    fn is_continuation(&self, line: &str) -> bool {
+        if line.trim().is_empty() {
+            return false;
+        }
+        if self.continuation.iter().any(|rule| rule.is_match(line)) {
+            return true;
+        }
+        match &self.start {
+            Some(start) => !start.is_match(line),
+            None => false,
+        }
+    }
+}
+
+/// Iterator returned by [`MultilineAssembler::assemble`].
+pub struct MultilineLines<I> {
+    assembler: MultilineAssembler,
+    lines: I,
+    done: bool,
+}
+
+impl<I> Iterator for MultilineLines<I>
+where
+    I: Iterator<Item = std::io::Result<(usize, String)>>,
+{
+    type Item = std::io::Result<(usize, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        for line_result in self.lines.by_ref() {
+            match line_result {
+                Ok((number, line)) => {
+                    if let Some(entry) = self.assembler.push(number, line) {
+                        return Some(Ok(entry));
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        self.done = true;
+        self.assembler.finish().map(Ok)
+    }
+}
@@ -0,0 +1,118 @@
+use crate::core::Result;
+use regex::Regex;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Suffixes of rotated files compressed by logrotate and friends
+const COMPRESSED_SUFFIX: &str = r"(?:\.(?:gz|bz2|xz|zst))?";
+
+// Dates of logrotate `dateext` and similar schemes: 20240101, 2024-01-01, 2024010112, ...
+// with an optional `-N` counter for several rotations on one day
+const DATE: &str = r"(\d{4}-?\d{2}-?\d{2}(?:\d{2}){0,3}(?:-\d+)?)";
+
+/// Position of a rotated file in its set; older rotations sort first.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Rotation {
+    // Digits of the date with the counter, compared numerically
+    Dated(Vec<u64>),
+    // `app.log.1` is newer than `app.log.2`
+    Numbered(Reverse<u64>),
+}
+
+/// Find the rotated siblings of a log file, oldest first and ending with the file itself.
+///
+/// Recognized names for `app.log` are `app.log.1`, `app.log.2.gz`, `app.1.log`,
+/// `app.log-20240101`, `app.log.20240101`, `app.log-20240101.gz` and
+/// `app-2024-01-01.log.gz`. Dated rotations come before numbered ones, as logrotate does not
+/// mix the two.
+pub fn rotated_set(path: &Path) -> Result<Vec<PathBuf>> {
+    let name = match path.file_name().and_then(|name| name.to_str()) {
+        Some(name) => name,
+        None => return Ok(vec![path.to_path_buf()]),
+    };
+    let (stem, extension) = match name.split_once('.') {
+        Some((stem, extension)) => (stem, format!(".{}", extension)),
+        None => (name, String::new()),
+    };
+    let (name, stem, extension) = (
+        regex::escape(name),
+        regex::escape(stem),
+        regex::escape(&extension),
+    );
+
+    let numbered = Regex::new(&format!(r"^{}\.(\d+){}$", name, COMPRESSED_SUFFIX))?;
+    let numbered_stem = Regex::new(&format!(
+        r"^{}\.(\d+){}{}$",
+        stem, extension, COMPRESSED_SUFFIX
+    ))?;
+    let dated = Regex::new(&format!(r"^{}[-._]{}{}$", name, DATE, COMPRESSED_SUFFIX))?;
+    let dated_stem = Regex::new(&format!(
+        r"^{}[-._]{}{}{}$",
+        stem, DATE, extension, COMPRESSED_SUFFIX
+    ))?;
+
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let mut rotated = Vec::new();
+    for dir_entry in fs::read_dir(directory)? {
+        let dir_entry = dir_entry?;
+        let file_name = dir_entry.file_name();
+        let file_name = match file_name.to_str() {
+            Some(file_name) => file_name,
+            None => continue,
+        };
+
+        // Dates are tried first: `app.log.20240101` is a date, not rotation number 20240101
+        let rotation = if let Some(captures) = dated
+            .captures(file_name)
+            .or_else(|| dated_stem.captures(file_name))
+        {
+            let parts = captures[1]
+                .split('-')
+                .filter_map(|part| part.parse().ok())
+                .collect();
+            Rotation::Dated(parts)
+        } else if let Some(captures) = numbered.captures(file_name).or_else(|| {
+            numbered_stem
+                .captures(file_name)
+                .filter(|_| !extension.is_empty())
+        }) {
+            match captures[1].parse() {
+                Ok(number) => Rotation::Numbered(Reverse(number)),
+                Err(_) => continue,
+            }
+        } else {
+            continue;
+        };
+
+        if dir_entry.path().is_file() {
+            rotated.push((rotation, path.with_file_name(file_name)));
+        }
+    }
+
+    rotated.sort();
+    let mut set: Vec<PathBuf> = rotated.into_iter().map(|(_, path)| path).collect();
+    set.push(path.to_path_buf());
+    Ok(set)
+}
+
+/// Replace every file by its rotated set. Files that already belong to the set of another
+/// file are read only as part of that set.
+pub fn rotated_sets(files: &[PathBuf]) -> Result<Vec<Vec<PathBuf>>> {
+    let sets = files
+        .iter()
+        .map(|file| rotated_set(file))
+        .collect::<Result<Vec<_>>>()?;
+
+    let rotated: HashSet<&PathBuf> = sets.iter().flat_map(|set| &set[..set.len() - 1]).collect();
+    Ok(sets
+        .iter()
+        .filter(|set| !rotated.contains(&set[set.len() - 1]))
+        .cloned()
+        .collect())
+}
@@ -0,0 +1,158 @@
+use crate::core::{LogParserError, Result};
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Expands the inputs given on the command line into the log files to read.
+///
+/// An input is a file, a directory (its files, or the whole tree when recursive) or a glob
+/// pattern such as `logs/**/*.log`. Patterns are matched here rather than by the shell, so
+/// quoted patterns behave the same everywhere. Include/exclude globs filter the files found
+/// in directories and by patterns and are matched against both the path and the file name;
+/// files named explicitly are always read.
+pub struct InputSelector {
+    recursive: bool,
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+impl InputSelector {
+    pub fn new(recursive: bool, include: &[String], exclude: &[String]) -> Result<Self> {
+        Ok(Self {
+            recursive,
+            include: glob_set(include)?,
+            exclude: glob_set(exclude)?,
+        })
+    }
+
+    /// Resolve the inputs in order, dropping files that were already selected.
+    pub fn resolve(&self, inputs: &[PathBuf]) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+
+        for input in inputs {
+            if input.is_dir() {
+                let depth = if self.recursive { usize::MAX } else { 1 };
+                files.extend(self.walk(input, depth, |_| true));
+            } else if input.exists() {
+                files.push(input.clone());
+            } else if is_glob(&input.to_string_lossy()) {
+                files.extend(self.expand(input)?);
+            } else {
+                return Err(not_found(format!(
+                    "{}: No such file or directory",
+                    input.display()
+                )));
+            }
+        }
+
+        let mut seen = HashSet::new();
+        files.retain(|file| seen.insert(file.clone()));
+        Ok(files)
+    }
+
+    fn expand(&self, pattern: &Path) -> Result<Vec<PathBuf>> {
+        let text = pattern.to_string_lossy();
+        let matcher = GlobBuilder::new(&text)
+            .literal_separator(true)
+            .build()
+            .map_err(glob_error)?
+            .compile_matcher();
+
+        // Walk from the directory before the first component with wildcards, only as deep
+        // as the pattern reaches unless it contains `**`
+        let base: PathBuf = pattern
+            .components()
+            .take_while(|component| !is_glob(&component.as_os_str().to_string_lossy()))
+            .collect();
+        let depth = if text.contains("**") {
+            usize::MAX
+        } else {
+            pattern.components().count() - base.components().count()
+        };
+
+        let files = if base.as_os_str().is_empty() || base.is_dir() {
+            self.walk(&base, depth, |path| matcher.is_match(path))
+        } else {
+            Vec::new()
+        };
+        if files.is_empty() {
+            return Err(not_found(format!("{}: No files match the pattern", text)));
+        }
+        Ok(files)
+    }
+
+    // Files below `root` accepted by `filter` and the include/exclude globs, sorted by name
+    fn walk(&self, root: &Path, depth: usize, filter: impl Fn(&Path) -> bool) -> Vec<PathBuf> {
+        let relative = root.as_os_str().is_empty();
+        let walker = WalkDir::new(if relative { Path::new(".") } else { root })
+            .max_depth(depth)
+            .sort_by_file_name()
+            .into_iter()
+            // Excluded directories are not descended into
+            .filter_entry(|entry| {
+                entry.depth() == 0
+                    || !entry.file_type().is_dir()
+                    || !glob_matches(self.exclude.as_ref(), entry.path())
+            });
+
+        let mut files = Vec::new();
+        for entry in walker {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    eprintln!("警告: {}", e);
+                    continue;
+                }
+            };
+            // Patterns without a directory match paths without the leading `./`
+            let path = match entry.path().strip_prefix(".") {
+                Ok(path) if relative => path,
+                _ => entry.path(),
+            };
+            if path.is_file() && filter(path) && self.selected(path) {
+                files.push(path.to_path_buf());
+            }
+        }
+        files
+    }
+
+    fn selected(&self, path: &Path) -> bool {
+        let included = self.include.is_none() || glob_matches(self.include.as_ref(), path);
+        included && !glob_matches(self.exclude.as_ref(), path)
+    }
+}
+
+fn glob_matches(set: Option<&GlobSet>, path: &Path) -> bool {
+    match set {
+        Some(set) => set.is_match(path) || path.file_name().is_some_and(|name| set.is_match(name)),
+        None => false,
+    }
+}
+
+fn glob_set(patterns: &[String]) -> Result<Option<GlobSet>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).map_err(glob_error)?);
+    }
+    builder.build().map(Some).map_err(glob_error)
+}
+
+fn is_glob(text: &str) -> bool {
+    text.contains(['*', '?', '[', '{'])
+}
+
+fn glob_error(e: globset::Error) -> LogParserError {
+    LogParserError::Config {
+        message: e.to_string(),
+    }
+}
+
+fn not_found(message: String) -> LogParserError {
+    LogParserError::Io(io::Error::new(io::ErrorKind::NotFound, message))
+}
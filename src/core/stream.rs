@@ -24,11 +24,9 @@ impl Default for BasicStreamProcessor {
 
 impl StreamProcessor for BasicStreamProcessor {
     fn process_file<P: AsRef<Path>>(&self, path: P) -> Result<Vec<LogEntry>> {
-        use std::fs::File;
-        use std::io::{BufRead, BufReader};
+        use std::io::BufRead;
 
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
+        let reader = crate::core::open_input(path)?;
         let lines = reader.lines().collect::<std::io::Result<Vec<_>>>()?;
 
         self.process_lines(lines.into_iter())
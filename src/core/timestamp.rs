@@ -0,0 +1,324 @@
+use crate::core::{FieldValue, LogParserError, Result};
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, Offset,
+    TimeZone, Utc,
+};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Timezone used to interpret timestamps that carry no zone of their own.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SourceTimezone {
+    #[default]
+    Utc,
+    Local,
+    Fixed(FixedOffset),
+    Named(chrono_tz::Tz),
+}
+
+impl std::str::FromStr for SourceTimezone {
+    type Err = LogParserError;
+
+    /// Accepts `UTC`, `local`, numeric offsets (`+09:00`, `+0900`, `-05`), the unambiguous
+    /// abbreviations `JST`/`KST` and IANA names such as `Asia/Tokyo`.
+    fn from_str(s: &str) -> Result<Self> {
+        let fixed =
+            |hours: i32| SourceTimezone::Fixed(FixedOffset::east_opt(hours * 3600).unwrap());
+
+        match s.to_uppercase().as_str() {
+            "UTC" | "Z" | "GMT" => return Ok(SourceTimezone::Utc),
+            "LOCAL" => return Ok(SourceTimezone::Local),
+            "JST" | "KST" => return Ok(fixed(9)),
+            _ => {}
+        }
+
+        if let Some(offset) = parse_offset(s) {
+            return Ok(SourceTimezone::Fixed(offset));
+        }
+
+        s.parse::<chrono_tz::Tz>()
+            .map(SourceTimezone::Named)
+            .map_err(|_| LogParserError::Config {
+                message: format!("Unknown timezone: {}", s),
+            })
+    }
+}
+
+impl SourceTimezone {
+    /// Convert a local time in this timezone to UTC. For times that occur twice
+    /// (DST fall-back) the earlier one is used; times skipped by DST yield `None`.
+    pub fn to_utc(&self, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            SourceTimezone::Utc => Some(naive.and_utc()),
+            SourceTimezone::Local => Local
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|dt| dt.with_timezone(&Utc)),
+            SourceTimezone::Fixed(offset) => offset
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|dt| dt.with_timezone(&Utc)),
+            SourceTimezone::Named(tz) => tz
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|dt| dt.with_timezone(&Utc)),
+        }
+    }
+
+    /// The current wall-clock time in this timezone.
+    pub fn now(&self) -> NaiveDateTime {
+        let now = Utc::now();
+        match self {
+            SourceTimezone::Utc => now.naive_utc(),
+            SourceTimezone::Local => now.with_timezone(&Local).naive_local(),
+            SourceTimezone::Fixed(offset) => now.with_timezone(offset).naive_local(),
+            SourceTimezone::Named(tz) => now.with_timezone(tz).naive_local(),
+        }
+    }
+}
+
+/// Recognizes the timestamp formats found in common logs:
+///
+/// - ISO 8601 / RFC 3339 with `T` or space, `-` or `/` date separators, fractional seconds of
+///   any precision (`.` or `,`) and `Z` or numeric offsets (`+09:00`, `+0900`, `+09`)
+/// - RFC 2822 (`Mon, 01 Jan 2024 12:00:00 +0900`)
+/// - Apache/Nginx access logs (`01/Jan/2024:12:00:00 +0900`)
+/// - ctime (`Mon Jan  1 12:00:00 2024`), as in Apache error logs
+/// - syslog (`Jan  1 12:00:00`), where the year is inferred
+/// - epoch seconds, milliseconds, microseconds and nanoseconds
+/// - Japanese style (`2024年1月1日 12時00分00秒`)
+///
+/// Timestamps without a zone are interpreted in the configured source timezone.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimestampParser {
+    timezone: SourceTimezone,
+}
+
+impl TimestampParser {
+    pub fn new(timezone: SourceTimezone) -> Self {
+        Self { timezone }
+    }
+
+    pub fn timezone(&self) -> SourceTimezone {
+        self.timezone
+    }
+
+    pub fn parse(&self, timestamp_str: &str) -> Option<DateTime<Utc>> {
+        let s = timestamp_str.trim();
+        if s.is_empty() {
+            return None;
+        }
+
+        self.parse_iso(s)
+            .or_else(|| {
+                DateTime::parse_from_rfc2822(s)
+                    .ok()
+                    .map(|dt| dt.with_timezone(&Utc))
+            })
+            .or_else(|| {
+                DateTime::parse_from_str(s, "%d/%b/%Y:%H:%M:%S %z")
+                    .ok()
+                    .map(|dt| dt.with_timezone(&Utc))
+            })
+            // ctime, as in Apache error logs: `Mon Jan 01 12:00:00.123456 2024`
+            .or_else(|| self.parse_with_format(s, "%a %b %e %H:%M:%S%.f %Y"))
+            .or_else(|| self.parse_syslog(s))
+            .or_else(|| self.parse_japanese(s))
+            .or_else(|| parse_epoch_str(s))
+    }
+
+    /// Parse with an explicit chrono format string; formats without a zone use the source timezone.
+    pub fn parse_with_format(&self, timestamp_str: &str, format: &str) -> Option<DateTime<Utc>> {
+        if let Ok(dt) = DateTime::parse_from_str(timestamp_str, format) {
+            return Some(dt.with_timezone(&Utc));
+        }
+        NaiveDateTime::parse_from_str(timestamp_str, format)
+            .ok()
+            .and_then(|naive| self.timezone.to_utc(naive))
+    }
+
+    /// Parse a structured field value: strings as above, numbers as epoch timestamps.
+    pub fn parse_value(&self, value: &FieldValue) -> Option<DateTime<Utc>> {
+        match value {
+            FieldValue::String(s) => self.parse(s),
+            FieldValue::Int(i) => {
+                parse_epoch_str(&i.to_string()).or_else(|| parse_epoch(*i as f64))
+            }
+            FieldValue::Float(f) => parse_epoch(*f),
+            FieldValue::Timestamp(ts) => Some(*ts),
+            _ => None,
+        }
+    }
+
+    /// Interpret a naive local time in the source timezone.
+    pub fn from_naive(&self, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+        self.timezone.to_utc(naive)
+    }
+
+    /// Parse a BSD syslog timestamp (`Oct 11 22:14:15`). The year is not part of the format:
+    /// assume the current one, unless that would put the entry more than a day in the future
+    /// (e.g. December logs read in January).
+    pub fn parse_syslog(&self, timestamp_str: &str) -> Option<DateTime<Utc>> {
+        let captures = syslog_regex().captures(timestamp_str)?;
+        let parse = |year: i32| {
+            NaiveDateTime::parse_from_str(
+                &format!(
+                    "{} {} {} {}",
+                    year, &captures[1], &captures[2], &captures[3]
+                ),
+                "%Y %b %d %H:%M:%S%.f",
+            )
+            .ok()
+        };
+
+        let now = self.timezone.now();
+        let mut naive = parse(now.year())?;
+        if naive > now + Duration::days(1) {
+            naive = parse(now.year() - 1)?;
+        }
+        self.timezone.to_utc(naive)
+    }
+
+    fn parse_iso(&self, s: &str) -> Option<DateTime<Utc>> {
+        let captures = iso_regex().captures(s)?;
+        let number = |i: usize| {
+            captures
+                .get(i)
+                .map_or(Some(0), |m| m.as_str().parse::<u32>().ok())
+        };
+
+        let date = NaiveDate::from_ymd_opt(captures[1].parse().ok()?, number(2)?, number(3)?)?;
+        let nanos = match captures.get(7) {
+            // Right-pad the fraction to nanoseconds, ignoring digits beyond that precision
+            Some(fraction) => format!("{:0<9}", &fraction.as_str()[..fraction.len().min(9)])
+                .parse::<u32>()
+                .ok()?,
+            None => 0,
+        };
+        let time = NaiveTime::from_hms_nano_opt(number(4)?, number(5)?, number(6)?, nanos)?;
+        let naive = date.and_time(time);
+
+        match captures.get(8) {
+            Some(offset) => {
+                let offset = parse_offset(offset.as_str())?;
+                Some(
+                    offset
+                        .from_local_datetime(&naive)
+                        .single()?
+                        .with_timezone(&Utc),
+                )
+            }
+            None => self.timezone.to_utc(naive),
+        }
+    }
+
+    fn parse_japanese(&self, s: &str) -> Option<DateTime<Utc>> {
+        let captures = japanese_regex().captures(s)?;
+        let number = |i: usize| {
+            captures
+                .get(i)
+                .map_or(Some(0), |m| m.as_str().parse::<u32>().ok())
+        };
+
+        let date = NaiveDate::from_ymd_opt(captures[1].parse().ok()?, number(2)?, number(3)?)?;
+        let time = NaiveTime::from_hms_opt(number(4)?, number(5)?, number(6)?)?;
+        self.timezone.to_utc(date.and_time(time))
+    }
+}
+
+/// Interpret a number as an epoch timestamp, guessing the unit from its magnitude.
+pub fn parse_epoch(value: f64) -> Option<DateTime<Utc>> {
+    let magnitude = value.abs();
+    let nanos = if magnitude >= 1e17 {
+        value
+    } else if magnitude >= 1e14 {
+        value * 1e3
+    } else if magnitude >= 1e11 {
+        value * 1e6
+    } else {
+        value * 1e9
+    };
+    Some(DateTime::from_timestamp_nanos(nanos as i64))
+}
+
+// Whole-string epoch values; integers keep their exact precision
+fn parse_epoch_str(s: &str) -> Option<DateTime<Utc>> {
+    if !epoch_regex().is_match(s) {
+        return None;
+    }
+
+    match s.parse::<i64>() {
+        Ok(value) => match s.trim_start_matches('-').len() {
+            0..=11 => DateTime::from_timestamp(value, 0),
+            12..=14 => DateTime::from_timestamp_millis(value),
+            15..=17 => DateTime::from_timestamp_micros(value),
+            _ => Some(DateTime::from_timestamp_nanos(value)),
+        },
+        // Fractional seconds are taken digit by digit, as f64 cannot hold them exactly
+        Err(_) => match s.split_once('.') {
+            Some((seconds, fraction)) if !s.starts_with('-') && seconds.len() <= 11 => {
+                let nanos = format!("{:0<9}", &fraction[..fraction.len().min(9)]);
+                DateTime::from_timestamp(seconds.parse().ok()?, nanos.parse().ok()?)
+            }
+            _ => parse_epoch(s.parse().ok()?),
+        },
+    }
+}
+
+// `Z`, `+09:00`, `+0900`, `+09` and `UTC`/`GMT` offsets
+fn parse_offset(s: &str) -> Option<FixedOffset> {
+    let s = s.trim();
+    if matches!(s, "Z" | "z" | "UTC" | "GMT") {
+        return Some(Utc.fix());
+    }
+
+    let captures = offset_regex().captures(s)?;
+    let hours: i32 = captures[2].parse().ok()?;
+    let minutes: i32 = captures
+        .get(3)
+        .map_or(Some(0), |m| m.as_str().parse().ok())?;
+    let seconds = hours * 3600 + minutes * 60;
+
+    if &captures[1] == "-" {
+        FixedOffset::west_opt(seconds)
+    } else {
+        FixedOffset::east_opt(seconds)
+    }
+}
+
+fn iso_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(
+            r"^(\d{4})[-/](\d{1,2})[-/](\d{1,2})(?:[T ]|\s+)(\d{1,2}):(\d{2})(?::(\d{2})(?:[.,](\d+))?)?\s*(Z|z|UTC|GMT|[+-]\d{2}(?::?\d{2})?)?$",
+        )
+        .unwrap()
+    })
+}
+
+fn syslog_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"^([A-Z][a-z]{2}) +(\d{1,2}) (\d{2}:\d{2}:\d{2}(?:\.\d+)?)$").unwrap()
+    })
+}
+
+fn japanese_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(
+            r"^(\d{4})年(\d{1,2})月(\d{1,2})日\s*(?:(\d{1,2})(?:時|:)(\d{1,2})(?:分|:)?(?:(\d{1,2})秒?)?)?$",
+        )
+        .unwrap()
+    })
+}
+
+fn epoch_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"^-?\d{9,19}(?:\.\d+)?$").unwrap())
+}
+
+fn offset_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"^([+-])(\d{2}):?(\d{2})?$").unwrap())
+}
@@ -0,0 +1,199 @@
+use crate::core::{FieldValue, LogEntry, LogParserError, Result};
+use crate::filters::Filter;
+use regex::Regex;
+use std::cmp::Ordering;
+use std::net::IpAddr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Match,
+    NotMatch,
+}
+
+/// Field names used for the client address by the different parsers, for `--ip`
+pub const CLIENT_ADDRESS_FIELDS: &[&str] = &[
+    "remote_addr",
+    "client_ip",
+    "remote_ip",
+    "c-ip",
+    "downstream_remote_ip",
+    "src",
+];
+
+/// Field names used for the HTTP response status by the different parsers, for `--status`
+pub const HTTP_STATUS_FIELDS: &[&str] = &[
+    "status",
+    "elb_status_code",
+    "http_status",
+    "sc-status",
+    "status_code",
+    "response_code",
+];
+
+/// Filter on a structured field, e.g. `status>=500`, `remote_addr=10.0.0.0/8` or `path~^/api`.
+///
+/// Integer fields accept status-class patterns such as `5xx`, and IP fields accept CIDR ranges.
+/// Alternative field names are separated by `|` (`status|sc-status=5xx`); the first one
+/// present in an entry is compared.
+pub struct FieldFilter {
+    keys: Vec<String>,
+    operator: Operator,
+    operand: String,
+    regex: Option<Regex>,
+}
+
+impl FieldFilter {
+    pub fn new(key: impl Into<String>, operator: &str, operand: impl Into<String>) -> Result<Self> {
+        let operator = match operator {
+            "=" | "==" => Operator::Eq,
+            "!=" => Operator::Ne,
+            ">" => Operator::Gt,
+            ">=" => Operator::Ge,
+            "<" => Operator::Lt,
+            "<=" => Operator::Le,
+            "~" => Operator::Match,
+            "!~" => Operator::NotMatch,
+            _ => {
+                return Err(LogParserError::Filter {
+                    message: format!("Unknown operator: {}", operator),
+                })
+            }
+        };
+        let operand = operand.into();
+        let regex = match operator {
+            Operator::Match | Operator::NotMatch => Some(Regex::new(&operand)?),
+            _ => None,
+        };
+
+        Ok(Self {
+            keys: key
+                .into()
+                .split('|')
+                .map(|key| key.trim().to_string())
+                .collect(),
+            operator,
+            operand,
+            regex,
+        })
+    }
+
+    /// Parse an expression of the form `<field><operator><value>`.
+    pub fn parse(expression: &str) -> Result<Self> {
+        let start =
+            expression
+                .find(['=', '!', '<', '>', '~'])
+                .ok_or_else(|| LogParserError::Filter {
+                    message: format!("Invalid field expression: {}", expression),
+                })?;
+        let rest = &expression[start..];
+        let operator_len = if rest.starts_with(">=")
+            || rest.starts_with("<=")
+            || rest.starts_with("!=")
+            || rest.starts_with("!~")
+            || rest.starts_with("==")
+        {
+            2
+        } else {
+            1
+        };
+
+        let key = expression[..start].trim();
+        if key.is_empty() {
+            return Err(LogParserError::Filter {
+                message: format!("Missing field name: {}", expression),
+            });
+        }
+
+        Self::new(key, &rest[..operator_len], rest[operator_len..].trim())
+    }
+
+    fn matches_value(&self, value: &FieldValue) -> bool {
+        if let Some(regex) = &self.regex {
+            let is_match = regex.is_match(&value.to_string());
+            return (self.operator == Operator::Match) == is_match;
+        }
+
+        if let Some(equal) = self.matches_pattern(value) {
+            return match self.operator {
+                Operator::Eq => equal,
+                Operator::Ne => !equal,
+                _ => false,
+            };
+        }
+
+        let ordering = match value.compare_str(&self.operand) {
+            Some(ordering) => ordering,
+            // Operands that cannot be read as the field's type only support (in)equality
+            None => return self.operator == Operator::Ne,
+        };
+
+        match self.operator {
+            Operator::Eq => ordering == Ordering::Equal,
+            Operator::Ne => ordering != Ordering::Equal,
+            Operator::Gt => ordering == Ordering::Greater,
+            Operator::Ge => ordering != Ordering::Less,
+            Operator::Lt => ordering == Ordering::Less,
+            Operator::Le => ordering != Ordering::Greater,
+            Operator::Match | Operator::NotMatch => unreachable!(),
+        }
+    }
+
+    // Patterns that only make sense for equality: `5xx` for integers and CIDR ranges for IPs
+    fn matches_pattern(&self, value: &FieldValue) -> Option<bool> {
+        match value {
+            FieldValue::Int(i) => {
+                let digits = self.operand.trim_end_matches(['x', 'X']);
+                let wildcards = self.operand.len() - digits.len();
+                if wildcards == 0 || digits.is_empty() {
+                    return None;
+                }
+                let prefix: i64 = digits.parse().ok()?;
+                // More wildcards than an i64 has digits: no value can match
+                let divisor = u32::try_from(wildcards)
+                    .ok()
+                    .and_then(|wildcards| 10_i64.checked_pow(wildcards));
+                Some(divisor.is_some_and(|divisor| i / divisor == prefix))
+            }
+            FieldValue::Ip(ip) => {
+                let (network, prefix_len) = self.operand.split_once('/')?;
+                let network: IpAddr = network.parse().ok()?;
+                let prefix_len: u32 = prefix_len.parse().ok()?;
+                Some(ip_in_network(ip, &network, prefix_len))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Filter for FieldFilter {
+    fn apply(&self, entry: &LogEntry) -> Result<bool> {
+        match self.keys.iter().find_map(|key| entry.field(key)) {
+            Some(value) => Ok(self.matches_value(value)),
+            None => Ok(false), // Entries without the field never match
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "field"
+    }
+}
+
+fn ip_in_network(ip: &IpAddr, network: &IpAddr, prefix_len: u32) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) if prefix_len <= 32 => {
+            let mask = u32::MAX.checked_shl(32 - prefix_len).unwrap_or(0);
+            u32::from(*ip) & mask == u32::from(*network) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) if prefix_len <= 128 => {
+            let mask = u128::MAX.checked_shl(128 - prefix_len).unwrap_or(0);
+            u128::from(*ip) & mask == u128::from(*network) & mask
+        }
+        _ => false,
+    }
+}
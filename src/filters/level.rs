@@ -1,14 +1,26 @@
 use crate::core::{LogEntry, LogLevel, Result};
 use crate::filters::Filter;
 
+/// Keeps entries at or above a level, or exactly at it.
 pub struct LevelFilter {
     target_level: LogLevel,
+    exact: bool,
 }
 
 impl LevelFilter {
+    /// Match `level` and every more severe level.
     pub fn new(level: LogLevel) -> Self {
         Self {
             target_level: level,
+            exact: false,
+        }
+    }
+
+    /// Match `level` only.
+    pub fn exact(level: LogLevel) -> Self {
+        Self {
+            target_level: level,
+            exact: true,
         }
     }
 }
@@ -16,7 +28,8 @@ impl LevelFilter {
 impl Filter for LevelFilter {
     fn apply(&self, entry: &LogEntry) -> Result<bool> {
         match &entry.level {
-            Some(level) => Ok(*level == self.target_level),
+            Some(level) if self.exact => Ok(*level == self.target_level),
+            Some(level) => Ok(*level >= self.target_level),
             None => Ok(false), // No level means we can't match
         }
     }
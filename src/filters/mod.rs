@@ -7,8 +7,10 @@ pub trait Filter {
 
 // Filter implementations will be added in subsequent phases
 pub mod composite;
+pub mod field;
 pub mod level;
 pub mod regex_filter;
 pub mod time;
 
+pub use field::FieldFilter;
 pub use level::LevelFilter;
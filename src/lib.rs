@@ -28,18 +28,30 @@ impl LogParser {
     }
 
     pub fn run(&mut self) -> Result<()> {
-        use crate::parsers::TextParser;
-        use crate::filters::LevelFilter;
-        use crate::output::{TextFormatter, json::JsonFormatter};
-        use std::fs::File;
-        use std::io::{BufRead, BufReader};
+        use crate::core::InputSelector;
+        use crate::filters::{FieldFilter, LevelFilter};
+        use crate::output::{TextFormatter, csv::CsvFormatter, gelf::GelfFormatter, json::JsonFormatter};
 
-        // Initialize parser
-        let parser = TextParser::new()?;
+        // Expand the file, directory and glob pattern arguments into the files to read
+        let files = InputSelector::new(
+            self.config.recursive,
+            &self.config.include,
+            &self.config.exclude,
+        )?
+        .resolve(&self.config.file_paths)?;
+
+        // Each file is read together with its rotated predecessors when requested
+        let sets = if self.config.rotated {
+            crate::core::rotated_sets(&files)?
+        } else {
+            files.into_iter().map(|file| vec![file]).collect()
+        };
+        let several = sets.len() > 1;
 
         // Initialize filter based on config
         let level_filter = if let Some(ref level_str) = self.config.level_filter {
             match level_str.parse::<LogLevel>() {
+                Ok(level) if self.config.level_exact => Some(LevelFilter::exact(level)),
                 Ok(level) => Some(LevelFilter::new(level)),
                 Err(_) => {
                     eprintln!("警告: 無効なログレベル '{}' - フィルタなしで処理を続行", level_str);
@@ -50,36 +62,272 @@ impl LogParser {
             None
         };
 
-        // Initialize output formatter based on config
+        // Initialize field filters (all of them must match)
+        let field_filters = self
+            .config
+            .field_filters
+            .iter()
+            .map(|expression| FieldFilter::parse(expression))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        // Initialize output formatter based on config; like grep, text output names the
+        // source file of each line when reading several files
+        let with_filename = self.config.with_filename.unwrap_or(several);
+        let text_formatter = || {
+            TextFormatter::default()
+                .with_fields(self.config.show_fields)
+                .with_source(with_filename)
+        };
         let formatter: Box<dyn OutputFormatter> = match self.config.output_format.as_str() {
             "json" => Box::new(JsonFormatter),
-            "text" => Box::new(TextFormatter::default()),
+            "csv" => Box::new(CsvFormatter),
+            "gelf" => Box::new(GelfFormatter),
+            "text" => Box::new(text_formatter()),
             _ => {
                 eprintln!("警告: 未対応の出力形式 '{}' - テキスト形式を使用", self.config.output_format);
-                Box::new(TextFormatter::default())
+                Box::new(text_formatter())
+            }
+        };
+
+        // Process files
+        let error_count = std::cell::Cell::new(0);
+        let streams = sets
+            .iter()
+            .map(|set| {
+                self.open_entries(set, several, level_filter.as_ref(), &field_filters, &error_count)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let output_count = if self.config.merge {
+            // Interleave the inputs by timestamp, writing entries as they are merged
+            let mut failure = None;
+            let written = {
+                let mut entries = crate::core::MergedEntries::new(streams)
+                    .map_while(|result| result.map_err(|e| failure = Some(e)).ok());
+                formatter.write_stream(&mut entries, &mut std::io::stdout().lock())
+            };
+            if let Some(e) = failure {
+                return Err(e.into());
+            }
+            match written {
+                Ok(written) => written,
+                // The reader went away (`| head`): stop quietly
+                Err(crate::core::LogParserError::Io(e))
+                    if e.kind() == std::io::ErrorKind::BrokenPipe =>
+                {
+                    return Ok(());
+                }
+                Err(e) => {
+                    eprintln!("出力フォーマットエラー: {}", e);
+                    return Err(e.into());
+                }
+            }
+        } else {
+            let mut parsed_entries = Vec::new();
+            for entry in streams.into_iter().flatten() {
+                parsed_entries.push(entry?);
+            }
+
+            // Output results
+            if !parsed_entries.is_empty() {
+                match formatter.format(&parsed_entries) {
+                    Ok(output) => println!("{}", output),
+                    Err(e) => {
+                        eprintln!("出力フォーマットエラー: {}", e);
+                        return Err(e.into());
+                    }
+                }
+            }
+            parsed_entries.len()
+        };
+
+        if output_count == 0 {
+            if error_count.get() > 0 {
+                eprintln!("エラー: {}個の解析エラーが発生しました", error_count.get());
+            } else {
+                eprintln!("該当するログエントリが見つかりませんでした");
+            }
+        }
+
+        Ok(())
+    }
+
+    // Open input files as one continuous stream of the entries that pass the filters;
+    // lines that cannot be parsed or filtered are reported and counted in `error_count`
+    fn open_entries<'a>(
+        &'a self,
+        paths: &[std::path::PathBuf],
+        several: bool,
+        level_filter: Option<&'a crate::filters::LevelFilter>,
+        field_filters: &'a [crate::filters::FieldFilter],
+        error_count: &'a std::cell::Cell<usize>,
+    ) -> Result<EntryStream<'a>> {
+        use std::cell::{Cell, RefCell};
+        use std::io::BufRead;
+        use std::rc::Rc;
+
+        let sources: Vec<String> = paths.iter().map(|path| path.display().to_string()).collect();
+
+        // Open each file when the previous one is exhausted, decompressing it if needed;
+        // journal export streams are binary-safe records rather than lines. Lines are
+        // numbered across the whole stream and `file_starts` holds the number of the first
+        // line of each file, so that entries can be traced back to their file.
+        let export = crate::parsers::journal::is_export_format(&self.config.input_format);
+        let files = paths.to_vec();
+        let file_starts = Rc::new(RefCell::new(Vec::new()));
+        let file_lines = {
+            let file_starts = Rc::clone(&file_starts);
+            let read = Rc::new(Cell::new(0));
+            move |path: std::path::PathBuf| {
+                file_starts.borrow_mut().push(read.get());
+                let lines: Box<dyn Iterator<Item = std::io::Result<String>>> =
+                    match crate::core::open_input(&path) {
+                        Ok(reader) if export => {
+                            Box::new(crate::parsers::JournalExportReader::new(reader))
+                        }
+                        Ok(reader) => Box::new(reader.lines()),
+                        Err(e) => Box::new(std::iter::once(Err(e))),
+                    };
+                let read = Rc::clone(&read);
+                lines.map(move |line| {
+                    let number = read.get();
+                    read.set(number + 1);
+                    line.map(|line| (number, line))
+                })
+            }
+        };
+        let mut lines: Box<dyn Iterator<Item = std::io::Result<(usize, String)>>> =
+            Box::new(files.into_iter().flat_map(file_lines));
+
+        // Initialize parser based on config; in auto mode the first lines are sampled
+        // to pick the parser and then processed like any other line
+        let mut sample = Vec::new();
+        let mut sample_numbers = Vec::new();
+        let parser = if self.config.input_format == "auto" {
+            for line_result in lines.by_ref().take(self.config.detect_sample_lines) {
+                let (number, line) = line_result?;
+                sample_numbers.push(number);
+                sample.push(line);
             }
+            let detection = crate::parsers::detect_parser(&sample, &self.config)?;
+            let prefix = if several { format!("{}: ", sources[0]) } else { String::new() };
+            eprintln!(
+                "{}入力形式を自動判定: {} (信頼度 {:.0}%)",
+                prefix,
+                detection.format,
+                detection.confidence * 100.0
+            );
+            detection.parser
+        } else {
+            crate::parsers::create_parser(&self.config.input_format, &self.config)?
+        };
+
+        // Join continuation lines (stack traces, ...) onto their entry when requested
+        let sample = sample_numbers.into_iter().zip(sample).map(Ok);
+        let lines = sample.chain(lines);
+        let records: Box<dyn Iterator<Item = std::io::Result<(usize, String)>>> = if self.config.multiline {
+            let assembler = crate::core::MultilineAssembler::new(
+                self.config.multiline_start.as_deref(),
+                &self.config.multiline_continue,
+            )?;
+            Box::new(assembler.assemble(lines))
+        } else {
+            Box::new(lines)
         };
 
-        // Process file
-        let file = File::open(&self.config.file_path)?;
-        let reader = BufReader::new(file);
-        let mut parsed_entries = Vec::new();
-        let mut line_count = 0;
-        let mut error_count = 0;
+        Ok(EntryStream {
+            parser,
+            records,
+            finished: false,
+            file_starts,
+            // Messages name the file only when there are several
+            name_files: several || sources.len() > 1,
+            sources,
+            last_line: 0,
+            level_filter,
+            field_filters,
+            error_count,
+        })
+    }
+}
+
+// Entries of one input parsed lazily, so that inputs can be merged without reading them
+// into memory first
+struct EntryStream<'a> {
+    parser: Box<dyn Parser>,
+    // Records with the stream-wide number of their first line
+    records: Box<dyn Iterator<Item = std::io::Result<(usize, String)>>>,
+    finished: bool,
+    file_starts: std::rc::Rc<std::cell::RefCell<Vec<usize>>>,
+    sources: Vec<String>,
+    name_files: bool,
+    // Number of the last record fed to the parser
+    last_line: usize,
+    level_filter: Option<&'a crate::filters::LevelFilter>,
+    field_filters: &'a [crate::filters::FieldFilter],
+    error_count: &'a std::cell::Cell<usize>,
+}
+
+impl EntryStream<'_> {
+    // Index of the file and line number within it of a stream-wide line number
+    fn locate(&self, number: usize) -> (usize, usize) {
+        let file_starts = self.file_starts.borrow();
+        let file = file_starts
+            .partition_point(|&start| start <= number)
+            .saturating_sub(1);
+        let start = file_starts.get(file).copied().unwrap_or(0);
+        (file, number - start + 1)
+    }
+
+    fn location(&self, number: usize) -> String {
+        let (file, line) = self.locate(number);
+        if self.name_files {
+            format!("{} {}行目", self.sources[file], line)
+        } else {
+            format!("{}行目", line)
+        }
+    }
+
+    fn report(&self, message: &str, number: usize, error: impl std::fmt::Display) {
+        eprintln!("{} ({}): {}", message, self.location(number), error);
+        self.error_count.set(self.error_count.get() + 1);
+    }
+}
+
+impl Iterator for EntryStream<'_> {
+    type Item = crate::core::Result<LogEntry>;
 
-        for line_result in reader.lines() {
-            let line = line_result?;
-            line_count += 1;
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            // At end of input, flush entries the parser still buffers
+            let result = match self.records.next() {
+                Some(Ok((number, line))) => {
+                    self.last_line = number;
+                    self.parser.feed_line(&line, number)
+                }
+                Some(Err(e)) => return Some(Err(e.into())),
+                None => match self.parser.finish() {
+                    Ok(None) => {
+                        self.finished = true;
+                        break;
+                    }
+                    result => result,
+                },
+            };
+
+            match result {
+                // Entries belong to the file of their first line, which may precede the
+                // line just read when the parser or multiline mode buffers lines
+                Ok(Some((first_line, entry))) => {
+                    let (file, _) = self.locate(first_line);
+                    let entry = entry.with_source(self.sources[file].as_str());
 
-            match parser.parse_line(&line) {
-                Ok(Some(entry)) => {
                     // Apply level filter if specified
-                    let should_include = if let Some(ref filter) = level_filter {
+                    let should_include = if let Some(filter) = self.level_filter {
                         match filter.apply(&entry) {
                             Ok(result) => result,
                             Err(e) => {
-                                eprintln!("フィルタエラー ({}行目): {}", line_count, e);
-                                error_count += 1;
+                                self.report("フィルタエラー", first_line, e);
                                 false
                             }
                         }
@@ -87,37 +335,20 @@ impl LogParser {
                         true
                     };
 
+                    let should_include = should_include
+                        && self.field_filters.iter().all(|filter| filter.apply(&entry).unwrap_or(false));
+
                     if should_include {
-                        parsed_entries.push(entry);
+                        return Some(Ok(entry));
                     }
                 },
                 Ok(None) => {
                     // Empty line or comment - skip silently
                 },
-                Err(e) => {
-                    eprintln!("解析エラー ({}行目): {}", line_count, e);
-                    error_count += 1;
-                }
+                Err(e) => self.report("解析エラー", self.last_line, e),
             }
         }
 
-        // Output results
-        if parsed_entries.is_empty() {
-            if error_count > 0 {
-                eprintln!("エラー: {}個の解析エラーが発生しました", error_count);
-            } else {
-                eprintln!("該当するログエントリが見つかりませんでした");
-            }
-        } else {
-            match formatter.format(&parsed_entries) {
-                Ok(output) => println!("{}", output),
-                Err(e) => {
-                    eprintln!("出力フォーマットエラー: {}", e);
-                    return Err(e.into());
-                }
-            }
-        }
-
-        Ok(())
+        None
     }
 }
@@ -1,4 +1,7 @@
 use clap::{Arg, ArgAction, Command};
+use log_parser::config::ConfigFile;
+use log_parser::core::SourceTimezone;
+use log_parser::filters::field::{CLIENT_ADDRESS_FIELDS, HTTP_STATUS_FIELDS};
 use log_parser::{Config, LogParser, Result};
 use std::path::PathBuf;
 
@@ -10,18 +13,73 @@ fn main() -> Result<()> {
         .about("高性能ログファイル解析・フィルタリングCLIツール")
         .arg(
             Arg::new("file")
-                .help("ログファイルのパス")
+                .help("ログファイル・ディレクトリ・glob パターン (例: 'logs/**/*.log') 複数指定可")
                 .required(true)
                 .value_name("FILE")
+                .num_args(1..)
                 .index(1),
         )
+        .arg(
+            Arg::new("recursive")
+                .long("recursive")
+                .short('r')
+                .help("ディレクトリを再帰的に読み込む")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("include")
+                .long("include")
+                .help("ディレクトリ・パターンから読み込むファイルの glob (例: '*.log') 複数指定可")
+                .value_name("GLOB")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("exclude")
+                .long("exclude")
+                .help("読み込まないファイル・ディレクトリの glob (例: '*.1') 複数指定可")
+                .value_name("GLOB")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("rotated")
+                .long("rotated")
+                .help("ローテーションされたファイル (app.log.1, app.log.2.gz, app-20240101.log.gz など) も古い順に続けて読み込む")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("merge")
+                .long("merge")
+                .help("複数の入力をタイムスタンプ順に統合して出力 (時刻のない行は直前のエントリに続ける)")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("with-filename")
+                .long("with-filename")
+                .short('H')
+                .help("テキスト出力の各行にファイル名を表示 (複数ファイルでは既定)")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("no-filename")
+                .long("no-filename")
+                .help("複数ファイルでもファイル名を表示しない")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("with-filename"),
+        )
         .arg(
             Arg::new("level")
                 .long("level")
                 .short('l')
-                .help("ログレベルでフィルタ (error, warn, info, debug)")
+                .help("指定レベル以上のログに絞り込み (trace, debug, info, notice, warn, error, critical, fatal, alert, emergency)")
                 .value_name("LEVEL"),
         )
+        .arg(
+            Arg::new("only-level")
+                .long("only-level")
+                .help("指定レベルのログのみ表示")
+                .value_name("LEVEL")
+                .conflicts_with("level"),
+        )
         .arg(
             Arg::new("since")
                 .long("since")
@@ -41,11 +99,122 @@ fn main() -> Result<()> {
                 .help("正規表現パターンで検索")
                 .value_name("PATTERN"),
         )
+        .arg(
+            Arg::new("where")
+                .long("where")
+                .short('w')
+                .help("フィールド条件でフィルタ (例: 'status>=500', 'path~^/api') 複数指定可")
+                .value_name("EXPR")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("ip")
+                .long("ip")
+                .help("クライアントIPアドレスでフィルタ (CIDR表記可)")
+                .value_name("ADDR"),
+        )
+        .arg(
+            Arg::new("status")
+                .long("status")
+                .help("HTTPステータスでフィルタ (例: 404, 5xx)")
+                .value_name("STATUS"),
+        )
+        .arg(
+            Arg::new("input-format")
+                .long("input-format")
+                .short('i')
+                .help("入力ログ形式 (auto, text, access, error, json, logfmt, csv, tsv, syslog, cef, leef, alb, elb, s3, cloudfront, w3c, haproxy, envoy, postgres, mysql, mysql-slow, docker, cri, journal, journal-export, gelf, または設定ファイルで定義した形式名)")
+                .value_name("FORMAT"),
+        )
+        .arg(
+            Arg::new("inner-format")
+                .long("inner-format")
+                .help("コンテナログ (docker, cri) 内のアプリケーションログの形式")
+                .value_name("FORMAT"),
+        )
+        .arg(
+            Arg::new("log-line-prefix")
+                .long("log-line-prefix")
+                .help("PostgreSQL ログの log_line_prefix (既定: '%m [%p] ')")
+                .value_name("PREFIX"),
+        )
+        .arg(
+            Arg::new("grok")
+                .long("grok")
+                .help("grok パターンで解析 (例: '%{COMBINEDAPACHELOG}') --input-format grok を暗黙指定")
+                .value_name("EXPR"),
+        )
+        .arg(
+            Arg::new("grok-patterns")
+                .long("grok-patterns")
+                .help("追加の grok パターンファイルまたはディレクトリ 複数指定可")
+                .value_name("PATH")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .short('c')
+                .help("設定ファイルのパス (省略時は標準の設定ディレクトリの config.toml)")
+                .value_name("PATH"),
+        )
+        .arg(
+            Arg::new("sample-lines")
+                .long("sample-lines")
+                .help("自動判定 (--input-format auto) に使う先頭の行数")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("100"),
+        )
+        .arg(
+            Arg::new("multiline")
+                .long("multiline")
+                .short('m')
+                .help("スタックトレース等の継続行を直前のエントリに結合")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("multiline-start")
+                .long("multiline-start")
+                .help("エントリ開始行の正規表現 (一致しない行は継続行として扱う) --multiline を暗黙指定")
+                .value_name("REGEX"),
+        )
+        .arg(
+            Arg::new("multiline-continue")
+                .long("multiline-continue")
+                .help("継続行の正規表現 (既定の規則を置き換え) 複数指定可 --multiline を暗黙指定")
+                .value_name("REGEX")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("timestamp-key")
+                .long("timestamp-key")
+                .help("タイムスタンプとして扱うキー名 (構造化ログ用)")
+                .value_name("KEY"),
+        )
+        .arg(
+            Arg::new("level-key")
+                .long("level-key")
+                .help("ログレベルとして扱うキー名 (構造化ログ用)")
+                .value_name("KEY"),
+        )
+        .arg(
+            Arg::new("message-key")
+                .long("message-key")
+                .help("メッセージとして扱うキー名 (構造化ログ用)")
+                .value_name("KEY"),
+        )
+        .arg(
+            Arg::new("timezone")
+                .long("timezone")
+                .help("タイムゾーン表記のない時刻の解釈 (UTC, local, +09:00, Asia/Tokyo など)")
+                .value_name("TZ"),
+        )
         .arg(
             Arg::new("format")
                 .long("format")
                 .short('f')
-                .help("出力形式 (text, json, csv)")
+                .help("出力形式 (text, json, csv, gelf)")
                 .value_name("FORMAT")
                 .default_value("text"),
         )
@@ -62,6 +231,12 @@ fn main() -> Result<()> {
                 .help("統計情報を表示")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("show-fields")
+                .long("show-fields")
+                .help("テキスト出力に抽出したフィールドを表示")
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("tui")
                 .long("tui")
@@ -70,17 +245,115 @@ fn main() -> Result<()> {
         )
         .get_matches();
 
-    let file_path = PathBuf::from(matches.get_one::<String>("file").unwrap());
+    let file_paths: Vec<PathBuf> = matches
+        .get_many::<String>("file")
+        .unwrap()
+        .map(PathBuf::from)
+        .collect();
+
+    // Settings from the config file; command line options take precedence
+    let config_file = match matches.get_one::<String>("config") {
+        Some(path) => ConfigFile::load(path)?,
+        None => ConfigFile::load_default()?,
+    };
+    let input_format = matches
+        .get_one::<String>("input-format")
+        .cloned()
+        .or_else(|| {
+            matches
+                .get_one::<String>("grok")
+                .map(|_| "grok".to_string())
+        })
+        .or(config_file.input_format)
+        .unwrap_or_else(|| "text".to_string());
+
+    let timezone = match matches
+        .get_one::<String>("timezone")
+        .or(config_file.timezone.as_ref())
+    {
+        Some(timezone) => timezone.parse()?,
+        None => SourceTimezone::Utc,
+    };
+
+    let mut field_filters: Vec<String> = matches
+        .get_many::<String>("where")
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default();
+    if let Some(ip) = matches.get_one::<String>("ip") {
+        field_filters.push(format!("{}={}", CLIENT_ADDRESS_FIELDS.join("|"), ip));
+    }
+    if let Some(status) = matches.get_one::<String>("status") {
+        field_filters.push(format!("{}={}", HTTP_STATUS_FIELDS.join("|"), status));
+    }
+
+    let multiline_start = matches.get_one::<String>("multiline-start").cloned();
+    let multiline_continue: Vec<String> = matches
+        .get_many::<String>("multiline-continue")
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default();
 
     let config = Config {
-        file_path,
-        level_filter: matches.get_one::<String>("level").cloned(),
+        file_paths,
+        recursive: matches.get_flag("recursive"),
+        rotated: matches.get_flag("rotated"),
+        merge: matches.get_flag("merge"),
+        include: matches
+            .get_many::<String>("include")
+            .map(|values| values.cloned().collect())
+            .unwrap_or_default(),
+        exclude: matches
+            .get_many::<String>("exclude")
+            .map(|values| values.cloned().collect())
+            .unwrap_or_default(),
+        with_filename: if matches.get_flag("with-filename") {
+            Some(true)
+        } else if matches.get_flag("no-filename") {
+            Some(false)
+        } else {
+            None
+        },
+        level_filter: matches
+            .get_one::<String>("level")
+            .or(matches.get_one::<String>("only-level"))
+            .cloned(),
+        level_exact: matches.contains_id("only-level"),
         since: matches.get_one::<String>("since").cloned(),
         until: matches.get_one::<String>("until").cloned(),
         grep_pattern: matches.get_one::<String>("grep").cloned(),
+        field_filters,
+        input_format,
+        inner_format: matches.get_one::<String>("inner-format").cloned(),
+        log_line_prefix: matches
+            .get_one::<String>("log-line-prefix")
+            .cloned()
+            .or(config_file.log_line_prefix),
+        multiline: matches.get_flag("multiline")
+            || multiline_start.is_some()
+            || !multiline_continue.is_empty(),
+        multiline_start,
+        multiline_continue,
+        detect_sample_lines: *matches.get_one::<usize>("sample-lines").unwrap(),
+        timestamp_key: matches.get_one::<String>("timestamp-key").cloned(),
+        level_key: matches.get_one::<String>("level-key").cloned(),
+        message_key: matches.get_one::<String>("message-key").cloned(),
+        timezone,
+        formats: config_file.formats,
+        grok_pattern: matches.get_one::<String>("grok").cloned(),
+        grok_pattern_files: config_file
+            .grok_pattern_files
+            .into_iter()
+            .chain(
+                matches
+                    .get_many::<String>("grok-patterns")
+                    .into_iter()
+                    .flatten()
+                    .map(PathBuf::from),
+            )
+            .collect(),
         output_format: matches.get_one::<String>("format").unwrap().clone(),
         follow: matches.get_flag("follow"),
         show_stats: matches.get_flag("stats"),
+        show_fields: matches.get_flag("show-fields"),
         tui_mode: matches.get_flag("tui"),
     };
 
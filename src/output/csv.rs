@@ -1,21 +1,143 @@
-// Stub implementation - to be implemented in later phases
-use crate::core::{LogEntry, Result};
+use crate::core::{LogEntry, LogParserError, Result};
 use crate::output::OutputFormatter;
+use std::collections::BTreeSet;
+
+// Columns written for every entry, before its fields
+const FIXED_COLUMNS: &[&str] = &["timestamp", "level", "message", "source"];
+
+// Last column of streamed output, holding the fields missing from the header
+const OTHER_FIELDS_COLUMN: &str = "fields";
 
 pub struct CsvFormatter;
 
+impl CsvFormatter {
+    // Fixed columns followed by every field name that appears in the entries
+    fn columns(entries: &[LogEntry]) -> Vec<String> {
+        let field_names: BTreeSet<&String> = entries
+            .iter()
+            .flat_map(|entry| entry.fields.keys())
+            .filter(|name| !FIXED_COLUMNS.contains(&name.as_str()))
+            .collect();
+
+        FIXED_COLUMNS
+            .iter()
+            .map(|name| name.to_string())
+            .chain(field_names.into_iter().cloned())
+            .collect()
+    }
+
+    fn record(entry: &LogEntry, columns: &[String]) -> Vec<String> {
+        columns
+            .iter()
+            .map(|column| match column.as_str() {
+                "timestamp" => entry
+                    .timestamp
+                    .map(|ts| ts.to_rfc3339())
+                    .unwrap_or_default(),
+                "level" => entry
+                    .level
+                    .as_ref()
+                    .map(|level| format!("{:?}", level))
+                    .unwrap_or_default(),
+                "message" => entry.message.clone(),
+                "source" => entry.source.clone().unwrap_or_default(),
+                name => entry
+                    .field(name)
+                    .map(|value| value.to_string())
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    fn write(entries: &[LogEntry], include_header: bool) -> Result<String> {
+        let columns = Self::columns(entries);
+        let mut lines = Vec::new();
+
+        if include_header {
+            lines.push(Self::line(&columns)?);
+        }
+        for entry in entries {
+            lines.push(Self::line(&Self::record(entry, &columns))?);
+        }
+        Ok(lines.join("\n"))
+    }
+
+    // Fields outside `field_columns` as key=value pairs, quoting values with spaces
+    fn other_fields(entry: &LogEntry, field_columns: &[String]) -> String {
+        let fields: Vec<String> = entry
+            .fields
+            .iter()
+            .filter(|(key, _)| !field_columns.contains(key))
+            .map(|(key, value)| {
+                let value = value.to_string();
+                if value.is_empty() || value.contains(char::is_whitespace) {
+                    format!("{}={:?}", key, value)
+                } else {
+                    format!("{}={}", key, value)
+                }
+            })
+            .collect();
+        fields.join(" ")
+    }
+
+    // One CSV line without its line terminator
+    fn line(record: &[String]) -> Result<String> {
+        let mut writer = csv::WriterBuilder::new()
+            .has_headers(false)
+            .from_writer(Vec::new());
+        writer.write_record(record).map_err(csv_error)?;
+
+        let bytes = writer.into_inner().map_err(|e| csv_error(e.into_error()))?;
+        let output = String::from_utf8(bytes).map_err(|e| LogParserError::Parse {
+            message: e.to_string(),
+        })?;
+        Ok(output.trim_end_matches('\n').to_string())
+    }
+}
+
 impl OutputFormatter for CsvFormatter {
-    fn format(&self, _entries: &[LogEntry]) -> Result<String> {
-        // Placeholder implementation
-        Ok("timestamp,level,message\n".to_string())
+    fn format(&self, entries: &[LogEntry]) -> Result<String> {
+        Self::write(entries, true)
     }
 
-    fn format_single(&self, _entry: &LogEntry) -> Result<String> {
-        // Placeholder implementation
-        Ok("".to_string())
+    fn format_single(&self, entry: &LogEntry) -> Result<String> {
+        Self::write(std::slice::from_ref(entry), false)
     }
 
     fn name(&self) -> &'static str {
         "csv"
     }
+
+    fn write_stream(
+        &self,
+        entries: &mut dyn Iterator<Item = LogEntry>,
+        out: &mut dyn std::io::Write,
+    ) -> Result<usize> {
+        // The columns are fixed by the first entry so that rows can be written as they come;
+        // fields of later entries without a column go to the last column as key=value pairs
+        let first = match entries.next() {
+            Some(entry) => entry,
+            None => return Ok(0),
+        };
+        let mut columns = Self::columns(std::slice::from_ref(&first));
+        columns.retain(|column| column != OTHER_FIELDS_COLUMN);
+        let field_columns = columns[FIXED_COLUMNS.len()..].to_vec();
+
+        let mut header = columns.clone();
+        header.push(OTHER_FIELDS_COLUMN.to_string());
+        writeln!(out, "{}", Self::line(&header)?)?;
+
+        let mut count = 0;
+        for entry in std::iter::once(first).chain(entries) {
+            let mut record = Self::record(&entry, &columns);
+            record.push(Self::other_fields(&entry, &field_columns));
+            writeln!(out, "{}", Self::line(&record)?)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+fn csv_error(e: impl Into<std::io::Error>) -> LogParserError {
+    LogParserError::Io(e.into())
 }
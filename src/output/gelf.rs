@@ -0,0 +1,115 @@
+use crate::core::{FieldValue, LogEntry, Result};
+use crate::output::OutputFormatter;
+use serde_json::{Map, Number, Value};
+
+// Fields that name the originating host, for GELF's mandatory `host`
+const HOST_FIELDS: &[&str] = &["host", "hostname", "_HOSTNAME"];
+
+/// Writes entries as GELF 1.1 messages, one JSON object per line.
+///
+/// The first line of the message is `short_message` and a multi-line message is also
+/// written as `full_message`. The level becomes its syslog severity, the input file
+/// `_source` and every field an additional `_` field; numbers stay numbers, other values
+/// are written as strings.
+pub struct GelfFormatter;
+
+impl GelfFormatter {
+    fn message(entry: &LogEntry) -> Value {
+        let mut message = Map::new();
+        message.insert("version".to_string(), Value::from("1.1"));
+
+        let host = HOST_FIELDS
+            .iter()
+            .find_map(|name| entry.field(name))
+            .map_or_else(|| "unknown".to_string(), |host| host.to_string());
+        message.insert("host".to_string(), Value::from(host));
+
+        let short_message = entry.message.lines().next().unwrap_or_default();
+        // GELF requires a non-empty short message
+        let short_message = if short_message.trim().is_empty() {
+            entry.raw_line.lines().next().unwrap_or("-")
+        } else {
+            short_message
+        };
+        message.insert("short_message".to_string(), Value::from(short_message));
+        if entry.message.contains('\n') {
+            message.insert(
+                "full_message".to_string(),
+                Value::from(entry.message.as_str()),
+            );
+        }
+
+        if let Some(timestamp) = entry.timestamp {
+            let seconds = timestamp.timestamp_millis() as f64 / 1000.0;
+            if let Some(seconds) = Number::from_f64(seconds) {
+                message.insert("timestamp".to_string(), Value::Number(seconds));
+            }
+        }
+        if let Some(level) = entry.level {
+            message.insert("level".to_string(), Value::from(level.syslog_severity()));
+        }
+
+        if let Some(source) = &entry.source {
+            message.insert("_source".to_string(), Value::from(source.as_str()));
+        }
+
+        for (name, value) in &entry.fields {
+            if HOST_FIELDS.contains(&name.as_str()) {
+                continue;
+            }
+            message.insert(additional_field_name(name), additional_field_value(value));
+        }
+
+        Value::Object(message)
+    }
+}
+
+impl OutputFormatter for GelfFormatter {
+    fn format(&self, entries: &[LogEntry]) -> Result<String> {
+        let lines = entries
+            .iter()
+            .map(|entry| self.format_single(entry))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(lines.join("\n"))
+    }
+
+    fn format_single(&self, entry: &LogEntry) -> Result<String> {
+        serde_json::to_string(&Self::message(entry)).map_err(|e| e.into())
+    }
+
+    fn name(&self) -> &'static str {
+        "gelf"
+    }
+}
+
+// GELF field names may only contain word characters, `.` and `-`; `_id` is reserved
+fn additional_field_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if name == "id" {
+        "_id_".to_string()
+    } else {
+        format!("_{}", name)
+    }
+}
+
+// GELF values are strings or numbers
+fn additional_field_value(value: &FieldValue) -> Value {
+    match value {
+        FieldValue::Int(i) => Value::from(*i),
+        FieldValue::Float(f) => {
+            Number::from_f64(*f).map_or_else(|| Value::from(f.to_string()), Value::Number)
+        }
+        FieldValue::Duration(d) => Value::from(d.as_secs_f64()),
+        other => Value::from(other.to_string()),
+    }
+}
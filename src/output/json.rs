@@ -1,6 +1,7 @@
 // Stub implementation - to be implemented in later phases
 use crate::core::{LogEntry, Result};
 use crate::output::OutputFormatter;
+use std::io::Write;
 
 pub struct JsonFormatter;
 
@@ -16,4 +17,23 @@ impl OutputFormatter for JsonFormatter {
     fn name(&self) -> &'static str {
         "json"
     }
+
+    fn write_stream(
+        &self,
+        entries: &mut dyn Iterator<Item = LogEntry>,
+        out: &mut dyn Write,
+    ) -> Result<usize> {
+        // The same array `format` writes, one element at a time
+        let mut count = 0;
+        for entry in entries {
+            let separator = if count == 0 { "[" } else { "," };
+            let element = self.format_single(&entry)?.replace('\n', "\n  ");
+            write!(out, "{}\n  {}", separator, element)?;
+            count += 1;
+        }
+        if count > 0 {
+            writeln!(out, "\n]")?;
+        }
+        Ok(count)
+    }
 }
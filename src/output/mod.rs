@@ -1,13 +1,30 @@
 use crate::core::{LogEntry, Result};
+use std::io::Write;
 
 pub trait OutputFormatter {
     fn format(&self, entries: &[LogEntry]) -> Result<String>;
     fn format_single(&self, entry: &LogEntry) -> Result<String>;
     fn name(&self) -> &'static str;
+
+    /// Write entries as they are produced instead of formatting them all at once, with the
+    /// same output as `format`. Returns the number of entries written.
+    fn write_stream(
+        &self,
+        entries: &mut dyn Iterator<Item = LogEntry>,
+        out: &mut dyn Write,
+    ) -> Result<usize> {
+        let mut count = 0;
+        for entry in entries {
+            writeln!(out, "{}", self.format_single(&entry)?)?;
+            count += 1;
+        }
+        Ok(count)
+    }
 }
 
 // Output formatter implementations
 pub mod csv;
+pub mod gelf;
 pub mod json;
 pub mod stats;
 pub mod text;
@@ -1,17 +1,72 @@
-use crate::core::{LogEntry, Result};
+use crate::core::{LogEntry, LogLevel, Result};
 use crate::output::OutputFormatter;
 use colored::*;
 
 pub struct TextFormatter {
     use_colors: bool,
+    show_fields: bool,
+    show_source: bool,
 }
 
 impl TextFormatter {
     pub fn new(use_colors: bool) -> Self {
-        Self { use_colors }
+        Self {
+            use_colors,
+            show_fields: false,
+            show_source: false,
+        }
+    }
+
+    pub fn with_fields(mut self, show_fields: bool) -> Self {
+        self.show_fields = show_fields;
+        self
+    }
+
+    /// Prefix each line with the file it was read from, like `grep -H`.
+    pub fn with_source(mut self, show_source: bool) -> Self {
+        self.show_source = show_source;
+        self
     }
 
     fn format_entry(&self, entry: &LogEntry) -> String {
+        let line = self.format_line(entry);
+        let line = match &entry.source {
+            Some(source) if self.show_source => {
+                if self.use_colors {
+                    format!("{}{}{}", source.magenta(), ":".cyan(), line)
+                } else {
+                    format!("{}:{}", source, line)
+                }
+            }
+            _ => line,
+        };
+        if !self.show_fields || entry.fields.is_empty() {
+            return line;
+        }
+
+        // Append structured fields as key=value pairs, quoting values with spaces
+        let fields: Vec<String> = entry
+            .fields
+            .iter()
+            .map(|(key, value)| {
+                let value = value.to_string();
+                if value.is_empty() || value.contains(char::is_whitespace) {
+                    format!("{}={:?}", key, value)
+                } else {
+                    format!("{}={}", key, value)
+                }
+            })
+            .collect();
+        let fields = fields.join(" ");
+
+        if self.use_colors {
+            format!("{} {}", line, fields.cyan())
+        } else {
+            format!("{} {}", line, fields)
+        }
+    }
+
+    fn format_line(&self, entry: &LogEntry) -> String {
         if !self.use_colors {
             return entry.raw_line.clone();
         }
@@ -20,10 +75,15 @@ impl TextFormatter {
         match &entry.level {
             Some(level) => {
                 let colored_line = match level {
-                    crate::core::LogLevel::Error => entry.raw_line.red(),
-                    crate::core::LogLevel::Warn => entry.raw_line.yellow(),
-                    crate::core::LogLevel::Info => entry.raw_line.white(),
-                    crate::core::LogLevel::Debug => entry.raw_line.bright_black(),
+                    LogLevel::Critical
+                    | LogLevel::Fatal
+                    | LogLevel::Alert
+                    | LogLevel::Emergency => entry.raw_line.red().bold(),
+                    LogLevel::Error => entry.raw_line.red(),
+                    LogLevel::Warn => entry.raw_line.yellow(),
+                    LogLevel::Notice => entry.raw_line.bright_white(),
+                    LogLevel::Info => entry.raw_line.white(),
+                    LogLevel::Debug | LogLevel::Trace => entry.raw_line.bright_black(),
                 };
                 colored_line.to_string()
             }
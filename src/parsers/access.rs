@@ -0,0 +1,104 @@
+use crate::core::{LogEntry, LogLevel, Result};
+use crate::parsers::Parser;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use std::net::IpAddr;
+
+/// Parser for Apache/Nginx access logs in Common or Combined Log Format.
+///
+/// `127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /a.gif HTTP/1.0" 200 2326 "-" "curl/8.0"`
+pub struct AccessLogParser {
+    line_regex: Regex,
+}
+
+impl AccessLogParser {
+    pub fn new() -> Result<Self> {
+        let line_regex = Regex::new(
+            r#"^(?P<remote_addr>\S+) (?P<ident>\S+) (?P<user>\S+) \[(?P<time>[^\]]+)\] "(?P<request>(?:[^"\\]|\\.)*)" (?P<status>\d{3}) (?P<bytes>\d+|-)(?: "(?P<referer>(?:[^"\\]|\\.)*)" "(?P<user_agent>(?:[^"\\]|\\.)*)")?"#,
+        )?;
+
+        Ok(Self { line_regex })
+    }
+}
+
+impl Default for AccessLogParser {
+    fn default() -> Self {
+        Self::new().expect("Failed to create default AccessLogParser")
+    }
+}
+
+impl Parser for AccessLogParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let mut entry = LogEntry::new(line.to_string());
+
+        // Lines that are not in access log format are kept as raw entries
+        let captures = match self.line_regex.captures(line) {
+            Some(captures) => captures,
+            None => return Ok(Some(entry)),
+        };
+
+        if let Some(timestamp) = parse_access_timestamp(&captures["time"]) {
+            entry = entry.with_timestamp(timestamp);
+        }
+
+        let remote_addr = &captures["remote_addr"];
+        entry = match remote_addr.parse::<IpAddr>() {
+            Ok(ip) => entry.with_field("remote_addr", ip),
+            Err(_) => entry.with_field("remote_addr", remote_addr),
+        };
+
+        for name in ["ident", "user", "referer", "user_agent"] {
+            if let Some(value) = captures.name(name) {
+                // "-" is the access log placeholder for a missing value
+                if value.as_str() != "-" {
+                    entry = entry.with_field(name, value.as_str());
+                }
+            }
+        }
+
+        if let Ok(bytes) = captures["bytes"].parse::<i64>() {
+            entry = entry.with_field("bytes", bytes);
+        }
+
+        let request = &captures["request"];
+        let mut parts = request.split(' ');
+        if let (Some(method), Some(path)) = (parts.next(), parts.next()) {
+            entry = entry.with_field("method", method).with_field("path", path);
+            if let Some(protocol) = parts.next() {
+                entry = entry.with_field("protocol", protocol);
+            }
+        }
+
+        let status: i64 = captures["status"].parse().unwrap_or_default();
+        entry = entry
+            .with_field("status", status)
+            .with_level(level_from_status(status))
+            .with_message(request.to_string());
+
+        Ok(Some(entry))
+    }
+
+    fn name(&self) -> &'static str {
+        "access"
+    }
+}
+
+// Parse the `10/Oct/2000:13:55:36 -0700` timestamp used by access logs
+fn parse_access_timestamp(timestamp_str: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_str(timestamp_str, "%d/%b/%Y:%H:%M:%S %z")
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+// Derive a log level from the HTTP status class so level filters work on access logs
+pub(crate) fn level_from_status(status: i64) -> LogLevel {
+    match status {
+        500..=599 => LogLevel::Error,
+        400..=499 => LogLevel::Warn,
+        _ => LogLevel::Info,
+    }
+}
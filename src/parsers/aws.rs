@@ -0,0 +1,295 @@
+use crate::core::{LogEntry, Result, TimestampParser};
+use crate::parsers::access::level_from_status;
+use crate::parsers::w3c::seconds_to_duration;
+use crate::parsers::Parser;
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// How a column of an AWS log line is interpreted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    /// The entry timestamp
+    Time,
+    /// A timestamp stored as a field
+    TimeField,
+    /// An HTTP status code; the one named first also sets the level
+    Status,
+    Int,
+    /// A latency in seconds (`-1` when unavailable)
+    Seconds,
+    /// A latency in milliseconds
+    Millis,
+    /// `ip:port`, stored as `<name>_ip` and `<name>_port`
+    AddrPort,
+    /// An IP address (or a string if it is not one)
+    Addr,
+    /// `METHOD url PROTOCOL`, which becomes the message
+    Request,
+    Text,
+}
+
+type Column = (&'static str, Kind);
+
+// https://docs.aws.amazon.com/elasticloadbalancing/latest/application/load-balancer-access-logs.html
+const ALB_COLUMNS: &[Column] = &[
+    ("type", Kind::Text),
+    ("time", Kind::Time),
+    ("elb", Kind::Text),
+    ("client", Kind::AddrPort),
+    ("target", Kind::AddrPort),
+    ("request_processing_time", Kind::Seconds),
+    ("target_processing_time", Kind::Seconds),
+    ("response_processing_time", Kind::Seconds),
+    ("elb_status_code", Kind::Status),
+    ("target_status_code", Kind::Status),
+    ("received_bytes", Kind::Int),
+    ("sent_bytes", Kind::Int),
+    ("request", Kind::Request),
+    ("user_agent", Kind::Text),
+    ("ssl_cipher", Kind::Text),
+    ("ssl_protocol", Kind::Text),
+    ("target_group_arn", Kind::Text),
+    ("trace_id", Kind::Text),
+    ("domain_name", Kind::Text),
+    ("chosen_cert_arn", Kind::Text),
+    ("matched_rule_priority", Kind::Int),
+    ("request_creation_time", Kind::TimeField),
+    ("actions_executed", Kind::Text),
+    ("redirect_url", Kind::Text),
+    ("error_reason", Kind::Text),
+    ("target_port_list", Kind::Text),
+    ("target_status_code_list", Kind::Text),
+    ("classification", Kind::Text),
+    ("classification_reason", Kind::Text),
+    ("conn_trace_id", Kind::Text),
+];
+
+// https://docs.aws.amazon.com/elasticloadbalancing/latest/classic/access-log-collection.html
+const ELB_COLUMNS: &[Column] = &[
+    ("timestamp", Kind::Time),
+    ("elb", Kind::Text),
+    ("client", Kind::AddrPort),
+    ("backend", Kind::AddrPort),
+    ("request_processing_time", Kind::Seconds),
+    ("backend_processing_time", Kind::Seconds),
+    ("response_processing_time", Kind::Seconds),
+    ("elb_status_code", Kind::Status),
+    ("backend_status_code", Kind::Status),
+    ("received_bytes", Kind::Int),
+    ("sent_bytes", Kind::Int),
+    ("request", Kind::Request),
+    ("user_agent", Kind::Text),
+    ("ssl_cipher", Kind::Text),
+    ("ssl_protocol", Kind::Text),
+];
+
+// https://docs.aws.amazon.com/AmazonS3/latest/userguide/LogFormat.html
+const S3_COLUMNS: &[Column] = &[
+    ("bucket_owner", Kind::Text),
+    ("bucket", Kind::Text),
+    ("time", Kind::Time),
+    ("remote_ip", Kind::Addr),
+    ("requester", Kind::Text),
+    ("request_id", Kind::Text),
+    ("operation", Kind::Text),
+    ("key", Kind::Text),
+    ("request_uri", Kind::Request),
+    ("http_status", Kind::Status),
+    ("error_code", Kind::Text),
+    ("bytes_sent", Kind::Int),
+    ("object_size", Kind::Int),
+    ("total_time", Kind::Millis),
+    ("turn_around_time", Kind::Millis),
+    ("referer", Kind::Text),
+    ("user_agent", Kind::Text),
+    ("version_id", Kind::Text),
+    ("host_id", Kind::Text),
+    ("signature_version", Kind::Text),
+    ("cipher_suite", Kind::Text),
+    ("authentication_type", Kind::Text),
+    ("host_header", Kind::Text),
+    ("tls_version", Kind::Text),
+    ("access_point_arn", Kind::Text),
+    ("acl_required", Kind::Text),
+];
+
+/// Parser for AWS access logs: Application Load Balancer, Classic Load Balancer and
+/// S3 server access logs (CloudFront logs are W3C, see [`W3cParser::cloudfront`]).
+///
+/// Columns become fields under the names of the AWS documentation, with typed latencies
+/// (durations), status codes and byte counts. AWS appends columns over time, so lines may
+/// have fewer or more columns than listed; extra ones are ignored.
+///
+/// [`W3cParser::cloudfront`]: crate::parsers::W3cParser::cloudfront
+pub struct AwsParser {
+    name: &'static str,
+    columns: &'static [Column],
+    // Fewer columns than this means the line is not in this format
+    min_columns: usize,
+    timestamp_parser: TimestampParser,
+}
+
+impl AwsParser {
+    pub fn alb() -> Self {
+        Self::with_columns("alb", ALB_COLUMNS, 12)
+    }
+
+    pub fn elb() -> Self {
+        Self::with_columns("elb", ELB_COLUMNS, 12)
+    }
+
+    pub fn s3() -> Self {
+        Self::with_columns("s3", S3_COLUMNS, 17)
+    }
+
+    fn with_columns(name: &'static str, columns: &'static [Column], min_columns: usize) -> Self {
+        Self {
+            name,
+            columns,
+            min_columns,
+            timestamp_parser: TimestampParser::default(),
+        }
+    }
+}
+
+impl Parser for AwsParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let mut entry = LogEntry::new(line.to_string());
+
+        let values = split_columns(line);
+        if values.len() < self.min_columns {
+            return Ok(Some(entry));
+        }
+
+        for (&(name, kind), value) in self.columns.iter().zip(&values) {
+            if value == "-" || value.is_empty() {
+                continue;
+            }
+
+            match kind {
+                Kind::Time => match self.timestamp_parser.parse(value) {
+                    Some(timestamp) => entry = entry.with_timestamp(timestamp),
+                    // Not a timestamp where one is required: not this format
+                    None => return Ok(Some(LogEntry::new(line.to_string()))),
+                },
+                Kind::TimeField => {
+                    if let Some(timestamp) = self.timestamp_parser.parse(value) {
+                        entry = entry.with_field(name, timestamp);
+                    }
+                }
+                Kind::Status => {
+                    if let Ok(status) = value.parse::<i64>() {
+                        if entry.level.is_none() {
+                            entry = entry.with_level(level_from_status(status));
+                        }
+                        entry = entry.with_field(name, status);
+                    }
+                }
+                Kind::Int => {
+                    if let Ok(number) = value.parse::<i64>() {
+                        entry = entry.with_field(name, number);
+                    }
+                }
+                Kind::Seconds => {
+                    if let Some(duration) = value.parse().ok().and_then(seconds_to_duration) {
+                        entry = entry.with_field(name, duration);
+                    }
+                }
+                Kind::Millis => {
+                    if let Ok(millis) = value.parse::<u64>() {
+                        entry = entry.with_field(name, Duration::from_millis(millis));
+                    }
+                }
+                Kind::AddrPort => {
+                    let (addr, port) = match value.rsplit_once(':') {
+                        Some((addr, port)) => (addr, port.parse::<i64>().ok()),
+                        None => (value.as_str(), None),
+                    };
+                    entry = with_addr(entry, &format!("{}_ip", name), addr);
+                    if let Some(port) = port {
+                        entry = entry.with_field(format!("{}_port", name), port);
+                    }
+                }
+                Kind::Addr => entry = with_addr(entry, name, value),
+                Kind::Request => {
+                    let mut parts = value.split(' ');
+                    if let (Some(method), Some(url)) = (parts.next(), parts.next()) {
+                        entry = entry.with_field("method", method).with_field("url", url);
+                        if let Some(protocol) = parts.next() {
+                            entry = entry.with_field("protocol", protocol);
+                        }
+                    }
+                    entry = entry.with_message(value.clone());
+                }
+                Kind::Text => entry = entry.with_field(name, value.as_str()),
+            }
+        }
+
+        Ok(Some(entry))
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+fn with_addr(entry: LogEntry, name: &str, addr: &str) -> LogEntry {
+    // IPv6 addresses may be bracketed in front of a port
+    let addr = addr.trim_start_matches('[').trim_end_matches(']');
+    match addr.parse::<IpAddr>() {
+        Ok(ip) => entry.with_field(name, ip),
+        Err(_) => entry.with_field(name, addr),
+    }
+}
+
+// Split on spaces; `"..."` and `[...]` are single columns (quotes and brackets removed)
+fn split_columns(line: &str) -> Vec<String> {
+    let mut columns = Vec::new();
+    let mut chars = line.trim().chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c == ' ' {
+            chars.next();
+            continue;
+        }
+
+        let mut column = String::new();
+        match c {
+            '"' => {
+                chars.next();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => column.extend(chars.next()),
+                        '"' => break,
+                        c => column.push(c),
+                    }
+                }
+            }
+            '[' => {
+                chars.next();
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    column.push(c);
+                }
+            }
+            _ => {
+                while let Some(&c) = chars.peek() {
+                    if c == ' ' {
+                        break;
+                    }
+                    column.push(c);
+                    chars.next();
+                }
+            }
+        }
+        columns.push(column);
+    }
+
+    columns
+}
@@ -0,0 +1,260 @@
+use crate::core::{Fields, LogEntry, Result, TimestampParser};
+use crate::parsers::json::flatten_object;
+use crate::parsers::Parser;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// One container log record, possibly a fragment of a longer line.
+struct Record {
+    timestamp: Option<DateTime<Utc>>,
+    stream: String,
+    text: String,
+    fields: Fields,
+    partial: bool,
+}
+
+// Fragments of partial lines waiting for their final part, per stream
+#[derive(Default)]
+struct PartialLines {
+    pending: BTreeMap<String, Partial>,
+}
+
+// A record with the number of its first line and its raw lines
+type Partial = (usize, Record, String);
+
+impl PartialLines {
+    // Returns the complete record once the final fragment arrives
+    fn push(&mut self, number: usize, record: Record, raw: &str) -> Option<Partial> {
+        let (number, record, raw) = match self.pending.remove(&record.stream) {
+            Some((first_number, mut first, mut first_raw)) => {
+                first.text.push_str(&record.text);
+                first.partial = record.partial;
+                first_raw.push('\n');
+                first_raw.push_str(raw);
+                (first_number, first, first_raw)
+            }
+            None => (number, record, raw.to_string()),
+        };
+
+        if record.partial {
+            self.pending
+                .insert(record.stream.clone(), (number, record, raw));
+            None
+        } else {
+            Some((number, record, raw))
+        }
+    }
+
+    fn pop(&mut self) -> Option<Partial> {
+        let stream = self.pending.keys().next()?.clone();
+        self.pending.remove(&stream)
+    }
+}
+
+// Parse the application line with the inner parser and add the container metadata
+fn build_entry(
+    inner: Option<&dyn Parser>,
+    record: Record,
+    raw: String,
+) -> Result<Option<LogEntry>> {
+    if record.text.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let mut entry = match inner {
+        Some(parser) => parser
+            .parse_line(&record.text)?
+            .unwrap_or_else(|| LogEntry::new(record.text.clone())),
+        None => LogEntry::new(record.text),
+    };
+    entry.raw_line = raw;
+    if entry.timestamp.is_none() {
+        entry.timestamp = record.timestamp;
+    }
+    entry.fields.extend(record.fields);
+
+    Ok(Some(entry.with_field("stream", record.stream)))
+}
+
+fn build_partial(
+    inner: Option<&dyn Parser>,
+    (number, record, raw): Partial,
+) -> Result<Option<(usize, LogEntry)>> {
+    Ok(build_entry(inner, record, raw)?.map(|entry| (number, entry)))
+}
+
+/// Parser for Docker's json-file logging driver:
+/// `{"log":"GET /health 200\n","stream":"stdout","time":"2024-01-01T12:00:00.123456789Z"}`.
+///
+/// Lines Docker split at 16 KiB (a `log` without trailing newline) are joined again. The
+/// application line can be parsed with an inner parser; `attrs` become `attrs.*` fields.
+pub struct DockerParser {
+    inner: Option<Box<dyn Parser>>,
+    timestamp_parser: TimestampParser,
+    partials: PartialLines,
+}
+
+impl DockerParser {
+    pub fn new(inner: Option<Box<dyn Parser>>) -> Self {
+        Self {
+            inner,
+            timestamp_parser: TimestampParser::default(),
+            partials: PartialLines::default(),
+        }
+    }
+
+    fn parse_record(&self, line: &str) -> Option<Record> {
+        let mut object = match serde_json::from_str::<Value>(line) {
+            Ok(Value::Object(object)) => object,
+            _ => return None,
+        };
+
+        let log = match object.remove("log") {
+            Some(Value::String(log)) => log,
+            _ => return None,
+        };
+        let (text, partial) = match log.strip_suffix('\n') {
+            Some(text) => (text.strip_suffix('\r').unwrap_or(text).to_string(), false),
+            None => (log, true),
+        };
+
+        let mut fields = Fields::new();
+        if let Some(Value::Object(attrs)) = object.remove("attrs") {
+            flatten_object("attrs", attrs, &mut fields);
+        }
+
+        Some(Record {
+            timestamp: object
+                .get("time")
+                .and_then(Value::as_str)
+                .and_then(|time| self.timestamp_parser.parse(time)),
+            stream: object
+                .get("stream")
+                .and_then(Value::as_str)
+                .unwrap_or("stdout")
+                .to_string(),
+            text,
+            fields,
+            partial,
+        })
+    }
+}
+
+impl Parser for DockerParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        match self.parse_record(line) {
+            Some(record) => build_entry(self.inner.as_deref(), record, line.to_string()),
+            None => Ok(Some(LogEntry::new(line.to_string()))),
+        }
+    }
+
+    fn feed_line(&mut self, line: &str, number: usize) -> Result<Option<(usize, LogEntry)>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        match self.parse_record(line) {
+            Some(record) => match self.partials.push(number, record, line) {
+                Some(partial) => build_partial(self.inner.as_deref(), partial),
+                None => Ok(None),
+            },
+            None => Ok(Some((number, LogEntry::new(line.to_string())))),
+        }
+    }
+
+    fn finish(&mut self) -> Result<Option<(usize, LogEntry)>> {
+        match self.partials.pop() {
+            Some(partial) => build_partial(self.inner.as_deref(), partial),
+            None => Ok(None),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "docker"
+    }
+}
+
+/// Parser for the Kubernetes CRI log format written by containerd and CRI-O:
+/// `2024-01-01T12:00:00.123456789Z stdout F GET /health 200`.
+///
+/// Partial (`P`) lines are joined with the following lines of the same stream up to the
+/// final (`F`) one. The application line can be parsed with an inner parser.
+pub struct CriParser {
+    line_regex: Regex,
+    inner: Option<Box<dyn Parser>>,
+    timestamp_parser: TimestampParser,
+    partials: PartialLines,
+}
+
+impl CriParser {
+    pub fn new(inner: Option<Box<dyn Parser>>) -> Result<Self> {
+        let line_regex = Regex::new(
+            r"^(?P<time>\S+) (?P<stream>stdout|stderr) (?P<tag>[A-Z])(?::\S*)? ?(?P<msg>.*)$",
+        )?;
+
+        Ok(Self {
+            line_regex,
+            inner,
+            timestamp_parser: TimestampParser::default(),
+            partials: PartialLines::default(),
+        })
+    }
+
+    fn parse_record(&self, line: &str) -> Option<Record> {
+        let captures = self.line_regex.captures(line)?;
+        // The first column must really be a timestamp, not just any word
+        let timestamp = self.timestamp_parser.parse(&captures["time"])?;
+
+        Some(Record {
+            timestamp: Some(timestamp),
+            stream: captures["stream"].to_string(),
+            text: captures["msg"].to_string(),
+            fields: Fields::new(),
+            partial: &captures["tag"] == "P",
+        })
+    }
+}
+
+impl Parser for CriParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        match self.parse_record(line) {
+            Some(record) => build_entry(self.inner.as_deref(), record, line.to_string()),
+            None => Ok(Some(LogEntry::new(line.to_string()))),
+        }
+    }
+
+    fn feed_line(&mut self, line: &str, number: usize) -> Result<Option<(usize, LogEntry)>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        match self.parse_record(line) {
+            Some(record) => match self.partials.push(number, record, line) {
+                Some(partial) => build_partial(self.inner.as_deref(), partial),
+                None => Ok(None),
+            },
+            None => Ok(Some((number, LogEntry::new(line.to_string())))),
+        }
+    }
+
+    fn finish(&mut self) -> Result<Option<(usize, LogEntry)>> {
+        match self.partials.pop() {
+            Some(partial) => build_partial(self.inner.as_deref(), partial),
+            None => Ok(None),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "cri"
+    }
+}
@@ -0,0 +1,224 @@
+use crate::core::{FieldValue, LogEntry, LogLevel, Result, SourceTimezone, TimestampParser};
+use crate::parsers::{KeyMapping, Parser};
+use std::collections::HashSet;
+use std::mem;
+
+/// Parser for CSV and TSV exports with a header row.
+///
+/// The first record names the columns. The columns matching the timestamp, level and message
+/// keys (case-insensitively) fill the entry and every other non-empty cell becomes a field
+/// under its column name. Quoted cells may span several lines. A first record that does not
+/// look like a header (values, or no timestamp, level or message column) leaves all records,
+/// itself included, raw entries, so that other formats are not mistaken for CSV during
+/// detection.
+pub struct CsvParser {
+    name: &'static str,
+    delimiter: u8,
+    mapping: KeyMapping,
+    columns: Vec<String>,
+    has_header: bool,
+    timestamp_column: Option<usize>,
+    level_column: Option<usize>,
+    message_column: Option<usize>,
+    // Lines of a record whose quoted cell is still open, and the number of the first one
+    pending: String,
+    pending_start: usize,
+    quoted: bool,
+    timestamp_parser: TimestampParser,
+}
+
+impl CsvParser {
+    pub fn csv(mapping: KeyMapping) -> Self {
+        Self::with_delimiter("csv", b',', mapping)
+    }
+
+    pub fn tsv(mapping: KeyMapping) -> Self {
+        Self::with_delimiter("tsv", b'\t', mapping)
+    }
+
+    fn with_delimiter(name: &'static str, delimiter: u8, mapping: KeyMapping) -> Self {
+        Self {
+            name,
+            delimiter,
+            mapping,
+            columns: Vec::new(),
+            has_header: false,
+            timestamp_column: None,
+            level_column: None,
+            message_column: None,
+            pending: String::new(),
+            pending_start: 0,
+            quoted: false,
+            timestamp_parser: TimestampParser::default(),
+        }
+    }
+
+    pub fn with_timezone(mut self, timezone: SourceTimezone) -> Self {
+        self.timestamp_parser = TimestampParser::new(timezone);
+        self
+    }
+
+    /// Column names of the header row, empty until it has been read.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    fn read_record(&self, text: &str) -> Option<Vec<String>> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .delimiter(self.delimiter)
+            .from_reader(text.as_bytes());
+        let record = reader.records().next()?.ok()?;
+        Some(record.iter().map(str::to_string).collect())
+    }
+
+    // Whether a quoted cell is open after `line`, given whether one was open before it.
+    // Only a quote at the start of a cell opens one; inside it `""` is an escaped quote.
+    fn quote_open(&self, line: &str, mut quoted: bool) -> bool {
+        let delimiter = char::from(self.delimiter);
+        let mut cell_start = !quoted;
+        let mut chars = line.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if quoted {
+                if c == '"' && chars.next_if_eq(&'"').is_none() {
+                    quoted = false;
+                }
+            } else if c == '"' && cell_start {
+                quoted = true;
+            }
+            cell_start = !quoted && c == delimiter;
+        }
+        quoted
+    }
+
+    // The first record names the columns; when it is not a header it is kept as a raw
+    // entry like the records after it
+    fn complete(&mut self, record: &str) -> Result<Option<(usize, LogEntry)>> {
+        if self.columns.is_empty() {
+            self.set_header(record);
+            if self.has_header {
+                return Ok(None);
+            }
+        }
+        Ok(self
+            .parse_line(record)?
+            .map(|entry| (self.pending_start, entry)))
+    }
+
+    fn set_header(&mut self, record: &str) {
+        self.columns = self
+            .read_record(record)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|column| column.trim_start_matches('\u{feff}').trim().to_string())
+            .collect();
+
+        let find = |keys: &[String]| {
+            keys.iter().find_map(|key| {
+                self.columns
+                    .iter()
+                    .position(|column| column.eq_ignore_ascii_case(key))
+            })
+        };
+        self.timestamp_column = find(&self.mapping.timestamp);
+        self.level_column = find(&self.mapping.level);
+        self.message_column = find(&self.mapping.message);
+
+        // Column names are distinct, non-empty and not values themselves, and at least one
+        // of them is a timestamp, level or message key
+        let mut names = HashSet::new();
+        let named = self.columns.iter().all(|column| {
+            !column.is_empty()
+                && names.insert(column.to_ascii_lowercase())
+                && matches!(FieldValue::infer(column), FieldValue::String(_))
+                && self.timestamp_parser.parse(column).is_none()
+        });
+        let mapped = self.timestamp_column.is_some()
+            || self.level_column.is_some()
+            || self.message_column.is_some();
+        self.has_header = named && mapped;
+    }
+}
+
+impl Parser for CsvParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let mut entry = LogEntry::new(line.to_string());
+
+        let values = match self.read_record(line) {
+            Some(values) if self.has_header => values,
+            // No header or not a record: keep the line as a raw entry
+            _ => return Ok(Some(entry)),
+        };
+
+        for (index, value) in values.into_iter().enumerate() {
+            if value.is_empty() {
+                continue;
+            }
+            let column = match self.columns.get(index) {
+                Some(column) => column.clone(),
+                // Cells beyond the header
+                None => format!("column{}", index + 1),
+            };
+
+            if Some(index) == self.timestamp_column {
+                if let Some(timestamp) = self.timestamp_parser.parse(&value) {
+                    entry = entry.with_timestamp(timestamp);
+                    continue;
+                }
+            } else if Some(index) == self.level_column {
+                if let Ok(level) = value.parse::<LogLevel>() {
+                    entry = entry.with_level(level);
+                    continue;
+                }
+            } else if Some(index) == self.message_column {
+                entry = entry.with_message(value);
+                continue;
+            }
+            entry = entry.with_field(column, FieldValue::infer(&value));
+        }
+
+        Ok(Some(entry))
+    }
+
+    fn feed_line(&mut self, line: &str, number: usize) -> Result<Option<(usize, LogEntry)>> {
+        if self.pending.is_empty() && line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        if self.pending.is_empty() {
+            self.pending_start = number;
+        } else {
+            self.pending.push('\n');
+        }
+        self.pending.push_str(line);
+
+        // A quoted cell left open continues on the next line
+        self.quoted = self.quote_open(line, self.quoted);
+        if self.quoted {
+            return Ok(None);
+        }
+
+        let record = mem::take(&mut self.pending);
+        self.complete(&record)
+    }
+
+    fn finish(&mut self) -> Result<Option<(usize, LogEntry)>> {
+        // A quoted cell left open at end of input
+        self.quoted = false;
+        let record = mem::take(&mut self.pending);
+        if record.is_empty() {
+            return Ok(None);
+        }
+        self.complete(&record)
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+}
@@ -0,0 +1,235 @@
+use crate::config::FormatDefinition;
+use crate::core::{
+    FieldValue, LogEntry, LogLevel, LogParserError, Result, SourceTimezone, TimestampParser,
+};
+use crate::parsers::grok::Grok;
+use crate::parsers::Parser;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use std::collections::HashMap;
+
+/// How a captured string is turned into a field value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Conversion {
+    /// Guess the type with `FieldValue::infer`
+    Auto,
+    String,
+    Int,
+    Float,
+}
+
+/// A capture group of the parser regex and the field it fills.
+///
+/// The field names `timestamp`, `level` and `message` fill the corresponding `LogEntry` members.
+#[derive(Debug, Clone)]
+pub struct Capture {
+    pub group: String,
+    pub field: String,
+    pub conversion: Conversion,
+}
+
+/// Parser for a user-defined format declared in the config file, or a compiled grok expression.
+pub struct CustomParser {
+    regex: Regex,
+    captures: Vec<Capture>,
+    timestamp_format: Option<String>,
+    timestamp_parser: TimestampParser,
+    levels: HashMap<String, LogLevel>,
+}
+
+impl CustomParser {
+    pub fn new(name: &str, definition: &FormatDefinition, grok: &Grok) -> Result<Self> {
+        let config_error = |message: String| LogParserError::Config {
+            message: format!("format '{}': {}", name, message),
+        };
+
+        let parser = match (&definition.regex, &definition.pattern, &definition.grok) {
+            (Some(regex), None, None) => Self::from_regex(Regex::new(regex)?),
+            (None, Some(pattern), None) => Self::from_regex(Regex::new(&format!(
+                "^{}$",
+                expand_placeholders(pattern).map_err(config_error)?
+            ))?),
+            (None, None, Some(expression)) => grok.compile(expression)?,
+            _ => {
+                return Err(config_error(
+                    "exactly one of 'pattern', 'regex' or 'grok' must be set".to_string(),
+                ))
+            }
+        };
+
+        let mut levels = HashMap::new();
+        for (raw, level_name) in &definition.levels {
+            let level = level_name.parse::<LogLevel>().map_err(config_error)?;
+            levels.insert(raw.to_lowercase(), level);
+        }
+
+        Ok(Self {
+            timestamp_format: definition.timestamp_format.clone(),
+            levels,
+            ..parser
+        })
+    }
+
+    /// Use every named capture group of `regex` as a field of the same name.
+    pub fn from_regex(regex: Regex) -> Self {
+        Self::with_captures(regex, Vec::new())
+    }
+
+    /// Use the given captures; named groups of `regex` not listed in `captures` are added as-is.
+    pub fn with_captures(regex: Regex, mut captures: Vec<Capture>) -> Self {
+        let listed: Vec<String> = captures.iter().map(|c| c.group.clone()).collect();
+        for name in regex.capture_names().flatten() {
+            if !listed.iter().any(|group| group == name) {
+                captures.push(Capture {
+                    group: name.to_string(),
+                    field: name.to_string(),
+                    conversion: Conversion::Auto,
+                });
+            }
+        }
+
+        Self {
+            regex,
+            captures,
+            timestamp_format: None,
+            timestamp_parser: TimestampParser::default(),
+            levels: HashMap::new(),
+        }
+    }
+
+    pub fn with_timezone(mut self, timezone: SourceTimezone) -> Self {
+        self.timestamp_parser = TimestampParser::new(timezone);
+        self
+    }
+
+    fn parse_timestamp(&self, timestamp_str: &str) -> Option<DateTime<Utc>> {
+        match &self.timestamp_format {
+            Some(format) => self
+                .timestamp_parser
+                .parse_with_format(timestamp_str, format),
+            None => self.timestamp_parser.parse(timestamp_str),
+        }
+    }
+
+    fn parse_level(&self, level_str: &str) -> Option<LogLevel> {
+        self.levels
+            .get(&level_str.to_lowercase())
+            .cloned()
+            .or_else(|| level_str.parse().ok())
+    }
+}
+
+impl Parser for CustomParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let mut entry = LogEntry::new(line.to_string());
+
+        // Lines that do not match the format are kept as raw entries
+        let captures = match self.regex.captures(line) {
+            Some(captures) => captures,
+            None => return Ok(Some(entry)),
+        };
+
+        for capture in &self.captures {
+            let value = match captures.name(&capture.group) {
+                Some(value) => value.as_str(),
+                None => continue,
+            };
+
+            match capture.field.as_str() {
+                "timestamp" => {
+                    if let Some(timestamp) = self.parse_timestamp(value) {
+                        entry = entry.with_timestamp(timestamp);
+                    }
+                }
+                "level" => {
+                    if let Some(level) = self.parse_level(value) {
+                        entry = entry.with_level(level);
+                    }
+                }
+                "message" => entry = entry.with_message(value.to_string()),
+                field => {
+                    if let Some(value) = convert(value, capture.conversion) {
+                        entry = entry.with_field(field, value);
+                    }
+                }
+            }
+        }
+
+        Ok(Some(entry))
+    }
+
+    fn name(&self) -> &'static str {
+        "custom"
+    }
+}
+
+fn convert(value: &str, conversion: Conversion) -> Option<FieldValue> {
+    match conversion {
+        Conversion::Auto => Some(FieldValue::infer(value)),
+        Conversion::String => Some(FieldValue::String(value.to_string())),
+        Conversion::Int => value.parse().ok().map(FieldValue::Int),
+        Conversion::Float => value.parse().ok().map(FieldValue::Float),
+    }
+}
+
+// Regex fragments available as `%{NAME}` / `%{NAME:field}` placeholders
+fn placeholder_regex(name: &str) -> Option<&'static str> {
+    let regex = match name {
+        "TIMESTAMP" => {
+            r"\d{4}[-/]\d{2}[-/]\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?"
+        }
+        "LEVEL" => r"[A-Za-z]+",
+        "MSG" | "GREEDYDATA" => r".*",
+        "DATA" => r".*?",
+        "WORD" => r"\w+",
+        "NOTSPACE" => r"\S+",
+        "SPACE" => r"\s*",
+        "INT" => r"[+-]?\d+",
+        "NUMBER" => r"[+-]?\d+(?:\.\d+)?",
+        "IP" => r"(?:\d{1,3}\.){3}\d{1,3}|[0-9A-Fa-f]*:[0-9A-Fa-f:.]+",
+        "QS" => r#""(?:[^"\\]|\\.)*""#,
+        _ => return None,
+    };
+    Some(regex)
+}
+
+// Translate placeholder syntax into a regex; literal text between placeholders is escaped.
+// `%{TIMESTAMP}`, `%{LEVEL}` and `%{MSG}` capture into the entry members by default.
+fn expand_placeholders(pattern: &str) -> std::result::Result<String, String> {
+    let mut regex = String::new();
+    let mut rest = pattern;
+
+    while let Some(start) = rest.find("%{") {
+        regex.push_str(&regex::escape(&rest[..start]));
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unterminated placeholder in '{}'", pattern))?;
+        let placeholder = &rest[start + 2..start + end];
+
+        let (name, capture) = match placeholder.split_once(':') {
+            Some((name, capture)) => (name, Some(capture)),
+            None => (placeholder, None),
+        };
+        let capture = capture.or(match name {
+            "TIMESTAMP" => Some("timestamp"),
+            "LEVEL" => Some("level"),
+            "MSG" => Some("message"),
+            _ => None,
+        });
+        let fragment = placeholder_regex(name)
+            .ok_or_else(|| format!("unknown placeholder '%{{{}}}'", name))?;
+
+        match capture {
+            Some(capture) => regex.push_str(&format!("(?P<{}>{})", capture, fragment)),
+            None => regex.push_str(&format!("(?:{})", fragment)),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    regex.push_str(&regex::escape(rest));
+
+    Ok(regex)
+}
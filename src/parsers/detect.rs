@@ -0,0 +1,96 @@
+use crate::config::Config;
+use crate::core::{LogEntry, Result};
+use crate::parsers::{create_parser, Parser, DETECTABLE_FORMATS};
+
+/// Result of input format detection.
+pub struct Detection {
+    pub format: String,
+    /// Average per-line score in `0.0..=1.0`
+    pub confidence: f64,
+    pub parser: Box<dyn Parser>,
+}
+
+/// Pick the parser that understands the sample lines best.
+///
+/// Every built-in format and every user-defined format from the config is scored; on a tie
+/// the format listed first wins, with plain text as the last resort. The returned parser is
+/// fresh: state built up while scoring (headers, partial lines) is not carried over.
+pub fn detect_parser(sample: &[String], config: &Config) -> Result<Detection> {
+    let candidates = DETECTABLE_FORMATS
+        .iter()
+        .map(|format| format.to_string())
+        .chain(config.formats.keys().cloned())
+        .chain(std::iter::once("text".to_string()));
+
+    let mut best: Option<(String, f64)> = None;
+    for format in candidates {
+        let mut parser = create_parser(&format, config)?;
+        let confidence = score(parser.as_mut(), sample);
+
+        if !best.as_ref().is_some_and(|(_, best)| confidence <= *best) {
+            best = Some((format, confidence));
+        }
+    }
+
+    // When no format understood a single line, the input is plain text
+    let (format, confidence) = match best {
+        Some((format, confidence)) if confidence > 0.0 => (format, confidence),
+        _ => ("text".to_string(), 0.0),
+    };
+    Ok(Detection {
+        parser: create_parser(&format, config)?,
+        format,
+        confidence,
+    })
+}
+
+fn score(parser: &mut dyn Parser, sample: &[String]) -> f64 {
+    let mut total = 0.0;
+    let mut lines = 0;
+
+    let mut results = sample
+        .iter()
+        .enumerate()
+        .map(|(number, line)| parser.feed_line(line, number))
+        .collect::<Vec<_>>();
+    // Flush every buffered entry, as a real run does
+    loop {
+        match parser.finish() {
+            Ok(None) => break,
+            result => results.push(result),
+        }
+    }
+
+    for result in results {
+        match result {
+            Ok(Some((_, entry))) => {
+                total += score_entry(&entry);
+                lines += 1;
+            }
+            Ok(None) => {}
+            Err(_) => lines += 1,
+        }
+    }
+
+    if lines == 0 {
+        0.0
+    } else {
+        total / lines as f64
+    }
+}
+
+// A timestamp is the strongest sign that the parser understood the line,
+// followed by a level and structured fields
+fn score_entry(entry: &LogEntry) -> f64 {
+    let mut score = 0.0;
+    if entry.timestamp.is_some() {
+        score += 0.5;
+    }
+    if entry.level.is_some() {
+        score += 0.25;
+    }
+    if !entry.fields.is_empty() {
+        score += 0.25;
+    }
+    score
+}
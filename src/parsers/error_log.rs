@@ -0,0 +1,163 @@
+use crate::core::{LogEntry, LogLevel, Result, SourceTimezone, TimestampParser};
+use crate::parsers::Parser;
+use regex::Regex;
+use std::net::IpAddr;
+
+/// Parser for Nginx and Apache error logs.
+///
+/// Nginx: `2024/01/01 12:00:00 [error] 1234#5678: *99 upstream timed out, client: 1.2.3.4,
+/// server: x, request: "GET / HTTP/1.1"`. The trailing `client:`, `server:`, `request:`,
+/// `upstream:`, ... context becomes fields and is removed from the message.
+///
+/// Apache 2.4 (and 2.2 without module and pid): `[Mon Jan 01 12:00:00.123456 2024]
+/// [core:error] [pid 1234:tid 5678] [client 1.2.3.4:5678] AH00126: Invalid URI`.
+pub struct ErrorLogParser {
+    nginx_regex: Regex,
+    nginx_context_regex: Regex,
+    apache_regex: Regex,
+    timestamp_parser: TimestampParser,
+}
+
+impl ErrorLogParser {
+    pub fn new() -> Result<Self> {
+        let nginx_regex = Regex::new(
+            r"^(?P<time>\d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2}) \[(?P<level>\w+)\] (?P<pid>\d+)#(?P<tid>\d+): (?:\*(?P<connection_id>\d+) )?(?P<message>.*)$",
+        )?;
+        let nginx_context_regex = Regex::new(
+            r#", (?P<key>[a-z_]+): (?:"(?P<quoted>(?:[^"\\]|\\.)*)"|(?P<plain>[^,]*))"#,
+        )?;
+        let apache_regex = Regex::new(
+            r"^\[(?P<time>[^\]]+)\] \[(?:(?P<module>[^:\]\s]+):)?(?P<level>\w+)\](?: \[pid (?P<pid>\d+)(?::tid (?P<tid>\d+))?\])?(?: \[(?:client|remote) (?P<client>[^\]]+)\])? ?(?P<message>(?:(?P<error_code>AH\d+): )?.*?)(?:, referer: (?P<referer>\S+))?$",
+        )?;
+
+        Ok(Self {
+            nginx_regex,
+            nginx_context_regex,
+            apache_regex,
+            timestamp_parser: TimestampParser::default(),
+        })
+    }
+
+    /// Timezone of the timestamps, which both servers log in local time without a zone.
+    pub fn with_timezone(mut self, timezone: SourceTimezone) -> Self {
+        self.timestamp_parser = TimestampParser::new(timezone);
+        self
+    }
+
+    fn parse_nginx(&self, line: &str) -> Option<LogEntry> {
+        let captures = self.nginx_regex.captures(line)?;
+        let mut entry = LogEntry::new(line.to_string());
+
+        if let Some(timestamp) = self.timestamp_parser.parse(&captures["time"]) {
+            entry = entry.with_timestamp(timestamp);
+        }
+        if let Some(level) = parse_level(&captures["level"]) {
+            entry = entry.with_level(level);
+        }
+        for name in ["pid", "tid", "connection_id"] {
+            if let Some(value) = captures
+                .name(name)
+                .and_then(|v| v.as_str().parse::<i64>().ok())
+            {
+                entry = entry.with_field(name, value);
+            }
+        }
+
+        // The context nginx appends always starts with the client address
+        let mut message = &captures["message"];
+        if let Some(start) = message.find(", client: ") {
+            for context in self.nginx_context_regex.captures_iter(&message[start..]) {
+                let value = context
+                    .name("quoted")
+                    .or_else(|| context.name("plain"))
+                    .map_or("", |value| value.as_str());
+                entry = match &context["key"] {
+                    "client" => with_client(entry, value),
+                    key => entry.with_field(key, value),
+                };
+            }
+            message = &message[..start];
+        }
+
+        Some(entry.with_message(message.to_string()))
+    }
+
+    fn parse_apache(&self, line: &str) -> Option<LogEntry> {
+        let captures = self.apache_regex.captures(line)?;
+        // The first bracket must really be a timestamp, not any bracketed word
+        let timestamp = self.timestamp_parser.parse(&captures["time"])?;
+        let mut entry = LogEntry::new(line.to_string()).with_timestamp(timestamp);
+
+        if let Some(level) = parse_level(&captures["level"]) {
+            entry = entry.with_level(level);
+        }
+        for name in ["pid", "tid"] {
+            if let Some(value) = captures
+                .name(name)
+                .and_then(|v| v.as_str().parse::<i64>().ok())
+            {
+                entry = entry.with_field(name, value);
+            }
+        }
+        for name in ["module", "error_code", "referer"] {
+            if let Some(value) = captures.name(name) {
+                entry = entry.with_field(name, value.as_str());
+            }
+        }
+        if let Some(client) = captures.name("client") {
+            entry = with_client(entry, client.as_str());
+        }
+
+        Some(entry.with_message(captures["message"].to_string()))
+    }
+}
+
+impl Default for ErrorLogParser {
+    fn default() -> Self {
+        Self::new().expect("Failed to create default ErrorLogParser")
+    }
+}
+
+impl Parser for ErrorLogParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let entry = self
+            .parse_nginx(line)
+            .or_else(|| self.parse_apache(line))
+            // Lines in neither format are kept as raw entries
+            .unwrap_or_else(|| LogEntry::new(line.to_string()));
+
+        Ok(Some(entry))
+    }
+
+    fn name(&self) -> &'static str {
+        "error"
+    }
+}
+
+// Apache has `trace1` to `trace8`; nginx and Apache share the syslog names otherwise
+fn parse_level(level: &str) -> Option<LogLevel> {
+    level
+        .trim_end_matches(|c: char| c.is_ascii_digit())
+        .parse()
+        .ok()
+}
+
+// `1.2.3.4` or `1.2.3.4:5678` (Apache adds the port); stored as `client_ip` and `client_port`
+fn with_client(entry: LogEntry, client: &str) -> LogEntry {
+    if let Ok(ip) = client.parse::<IpAddr>() {
+        return entry.with_field("client_ip", ip);
+    }
+    if let Some((addr, port)) = client.rsplit_once(':') {
+        let addr = addr.trim_start_matches('[').trim_end_matches(']');
+        if let (Ok(ip), Ok(port)) = (addr.parse::<IpAddr>(), port.parse::<i64>()) {
+            return entry
+                .with_field("client_ip", ip)
+                .with_field("client_port", port);
+        }
+    }
+    entry.with_field("client_ip", client)
+}
@@ -0,0 +1,102 @@
+use crate::core::{parse_epoch, Fields, LogEntry, LogLevel, Result, TimestampParser};
+use crate::parsers::json::flatten_object;
+use crate::parsers::Parser;
+use serde_json::{Map, Value};
+
+/// Parser for Graylog Extended Log Format messages, one JSON object per line:
+/// `{"version":"1.1","host":"web1","short_message":"boom","timestamp":1704110400.123,"level":3,"_user_id":42}`.
+///
+/// `full_message` (or `short_message` when there is none) is the message, `level` is a
+/// syslog severity and `_`-prefixed additional fields become fields without the underscore.
+pub struct GelfParser {
+    timestamp_parser: TimestampParser,
+}
+
+impl GelfParser {
+    pub fn new() -> Self {
+        Self {
+            timestamp_parser: TimestampParser::default(),
+        }
+    }
+}
+
+impl Default for GelfParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for GelfParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let mut entry = LogEntry::new(line.to_string());
+
+        let object = match serde_json::from_str::<Value>(line) {
+            Ok(Value::Object(object)) if object.contains_key("short_message") => object,
+            // Not a GELF message: keep the line as a raw entry
+            _ => return Ok(Some(entry)),
+        };
+
+        let mut short_message = None;
+        let mut full_message = None;
+        let mut additional = Map::new();
+        for (key, value) in object {
+            match (key.as_str(), value) {
+                ("version", _) => {}
+                ("short_message", Value::String(message)) => short_message = Some(message),
+                ("full_message", Value::String(message)) => full_message = Some(message),
+                ("timestamp", Value::Number(seconds)) => {
+                    let timestamp = self
+                        .timestamp_parser
+                        .parse(&seconds.to_string())
+                        .or_else(|| seconds.as_f64().and_then(parse_epoch));
+                    if let Some(timestamp) = timestamp {
+                        entry = entry.with_timestamp(timestamp);
+                    }
+                }
+                ("timestamp", Value::String(timestamp)) => {
+                    if let Some(timestamp) = self.timestamp_parser.parse(&timestamp) {
+                        entry = entry.with_timestamp(timestamp);
+                    }
+                }
+                ("level", Value::Number(level)) => {
+                    if let Some(level) = level
+                        .as_u64()
+                        .and_then(|level| u8::try_from(level).ok())
+                        .and_then(LogLevel::from_syslog_severity)
+                    {
+                        entry = entry.with_level(level);
+                    }
+                }
+                ("level", Value::String(level)) => {
+                    if let Ok(level) = level.parse::<LogLevel>() {
+                        entry = entry.with_level(level);
+                    }
+                }
+                // Additional fields; `host` and the deprecated GELF 1.0 fields
+                // (`facility`, `file`, `line`) are kept under their own names
+                (key, value) => {
+                    let key = key.strip_prefix('_').unwrap_or(key);
+                    additional.insert(key.to_string(), value);
+                }
+            }
+        }
+
+        if let Some(message) = full_message.or(short_message) {
+            entry = entry.with_message(message);
+        }
+
+        let mut fields = Fields::new();
+        flatten_object("", additional, &mut fields);
+        entry.fields = fields;
+
+        Ok(Some(entry))
+    }
+
+    fn name(&self) -> &'static str {
+        "gelf"
+    }
+}
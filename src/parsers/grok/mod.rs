@@ -0,0 +1,179 @@
+use crate::core::{LogParserError, Result};
+use crate::parsers::custom::{Capture, Conversion, CustomParser};
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+
+const BUILTIN_PATTERNS: &str = include_str!("patterns.grok");
+
+// Nesting limit for pattern references, which also catches reference cycles
+const MAX_DEPTH: usize = 64;
+
+/// Grok pattern engine on top of the `regex` crate.
+///
+/// Expressions reference library patterns as `%{NAME}`, `%{NAME:field}` or
+/// `%{NAME:field:type}` (type is `int`, `float` or `string`). Field names written as
+/// `[http][status]` are stored as `http.status`.
+pub struct Grok {
+    patterns: HashMap<String, String>,
+    reference_regex: Regex,
+}
+
+impl Grok {
+    /// Create an engine with the standard pattern library.
+    pub fn new() -> Result<Self> {
+        let mut grok = Self::empty()?;
+        grok.add_patterns(BUILTIN_PATTERNS)?;
+        Ok(grok)
+    }
+
+    /// Create an engine without any patterns.
+    pub fn empty() -> Result<Self> {
+        let reference_regex =
+            Regex::new(r"%\{(?P<name>\w+)(?::(?P<field>[^:}]+))?(?::(?P<type>[^:}]+))?\}")?;
+
+        Ok(Self {
+            patterns: HashMap::new(),
+            reference_regex,
+        })
+    }
+
+    /// Create an engine with the standard library plus the given pattern files or directories.
+    pub fn with_pattern_files<P: AsRef<Path>>(paths: &[P]) -> Result<Self> {
+        let mut grok = Self::new()?;
+        for path in paths {
+            grok.load_patterns(path)?;
+        }
+        Ok(grok)
+    }
+
+    pub fn add_pattern(&mut self, name: impl Into<String>, regex: impl Into<String>) {
+        self.patterns.insert(name.into(), regex.into());
+    }
+
+    /// Add patterns in pattern file syntax: one `NAME regex` per line, `#` starts a comment.
+    pub fn add_patterns(&mut self, definitions: &str) -> Result<()> {
+        for line in definitions.lines() {
+            let line = line.trim_start();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.split_once(char::is_whitespace) {
+                Some((name, regex)) => self.add_pattern(name, regex.trim()),
+                None => {
+                    return Err(LogParserError::Config {
+                        message: format!("Invalid grok pattern definition: {}", line),
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Load a pattern file, or every file in a pattern directory.
+    pub fn load_patterns<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if path.is_dir() {
+            let mut files = std::fs::read_dir(path)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<std::io::Result<Vec<_>>>()?;
+            // Later files override earlier ones, so load them in a stable order
+            files.sort();
+            for file in files.into_iter().filter(|file| file.is_file()) {
+                self.add_patterns(&std::fs::read_to_string(file)?)?;
+            }
+            Ok(())
+        } else {
+            self.add_patterns(&std::fs::read_to_string(path)?)
+        }
+    }
+
+    /// Compile a grok expression into a parser whose named captures become fields.
+    pub fn compile(&self, expression: &str) -> Result<CustomParser> {
+        let mut captures = Vec::new();
+        let regex = self.expand(expression, &mut captures, 0)?;
+        Ok(CustomParser::with_captures(Regex::new(&regex)?, captures))
+    }
+
+    fn expand(
+        &self,
+        expression: &str,
+        captures: &mut Vec<Capture>,
+        depth: usize,
+    ) -> Result<String> {
+        if depth > MAX_DEPTH {
+            return Err(grok_error(format!(
+                "pattern nesting too deep (recursive reference?) in '{}'",
+                expression
+            )));
+        }
+
+        let mut regex = String::new();
+        let mut last = 0;
+
+        for reference in self.reference_regex.captures_iter(expression) {
+            let whole = reference.get(0).unwrap();
+            regex.push_str(&expression[last..whole.start()]);
+            last = whole.end();
+
+            let name = &reference["name"];
+            let definition = self
+                .patterns
+                .get(name)
+                .ok_or_else(|| grok_error(format!("unknown pattern '{}'", name)))?;
+
+            // Reserve the group before expanding so outer captures keep a lower index
+            let capture_index = reference.name("field").map(|field| {
+                captures.push(Capture {
+                    group: format!("__grok{}", captures.len()),
+                    field: normalize_field_name(field.as_str()),
+                    conversion: Conversion::Auto,
+                });
+                captures.len() - 1
+            });
+
+            if let Some(index) = capture_index {
+                if let Some(conversion) = reference.name("type") {
+                    captures[index].conversion = match conversion.as_str() {
+                        "int" | "integer" => Conversion::Int,
+                        "float" => Conversion::Float,
+                        "string" => Conversion::String,
+                        other => return Err(grok_error(format!("unknown type '{}'", other))),
+                    };
+                }
+            }
+
+            let expanded = self.expand(definition, captures, depth + 1)?;
+            match capture_index {
+                Some(index) => {
+                    regex.push_str(&format!("(?P<{}>{})", captures[index].group, expanded))
+                }
+                None => regex.push_str(&format!("(?:{})", expanded)),
+            }
+        }
+        regex.push_str(&expression[last..]);
+
+        Ok(regex)
+    }
+}
+
+// `[http][status]` (Logstash field reference syntax) becomes `http.status`
+fn normalize_field_name(field: &str) -> String {
+    if field.starts_with('[') {
+        field
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .split("][")
+            .collect::<Vec<_>>()
+            .join(".")
+    } else {
+        field.to_string()
+    }
+}
+
+fn grok_error(message: String) -> LogParserError {
+    LogParserError::Config {
+        message: format!("grok: {}", message),
+    }
+}
@@ -0,0 +1,196 @@
+use crate::core::{FieldValue, LogEntry, LogLevel, Result};
+use crate::parsers::Parser;
+use chrono::DateTime;
+use serde_json::{Map, Value};
+use std::io::{self, BufRead, Read};
+
+/// Parser for systemd journal entries as written by `journalctl -o json`.
+///
+/// `MESSAGE`, `PRIORITY` and `__REALTIME_TIMESTAMP` fill the entry; other user and trusted
+/// fields (`_SYSTEMD_UNIT`, `_PID`, `_HOSTNAME`, `SYSLOG_IDENTIFIER`, ...) are kept under their
+/// journal names. Address fields such as `__CURSOR` are dropped.
+/// `journalctl -o export` input is converted to the same JSON by [`JournalExportReader`].
+pub struct JournalParser;
+
+impl JournalParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for JournalParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for JournalParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let mut entry = LogEntry::new(line.to_string());
+
+        let object = match serde_json::from_str::<Value>(line) {
+            Ok(Value::Object(object)) if object.contains_key("__REALTIME_TIMESTAMP") => object,
+            // Not a journal entry: keep the line as a raw entry
+            _ => return Ok(Some(entry)),
+        };
+
+        for (key, value) in object {
+            let value = match journal_value(value) {
+                Some(value) => value,
+                None => continue,
+            };
+
+            match key.as_str() {
+                "MESSAGE" => entry = entry.with_message(value),
+                "PRIORITY" => match value.parse().ok().and_then(LogLevel::from_syslog_severity) {
+                    Some(level) => entry = entry.with_level(level),
+                    None => entry = entry.with_field(key, value),
+                },
+                "__REALTIME_TIMESTAMP" => {
+                    // Microseconds since the epoch
+                    if let Some(timestamp) =
+                        value.parse().ok().and_then(DateTime::from_timestamp_micros)
+                    {
+                        entry = entry.with_timestamp(timestamp);
+                    }
+                }
+                key if key.starts_with("__") => {}
+                _ => entry = entry.with_field(key, FieldValue::infer(&value)),
+            }
+        }
+
+        Ok(Some(entry))
+    }
+
+    fn name(&self) -> &'static str {
+        "journal"
+    }
+}
+
+// Journal values are strings, byte arrays for non-UTF-8 data, or arrays of those for
+// fields that occur more than once (the first occurrence is used)
+fn journal_value(value: Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Array(items) if items.iter().all(Value::is_u64) => {
+            let bytes: Vec<u8> = items
+                .iter()
+                .filter_map(|b| b.as_u64())
+                .map(|b| b as u8)
+                .collect();
+            Some(String::from_utf8_lossy(&bytes).into_owned())
+        }
+        Value::Array(items) => items.into_iter().find_map(journal_value),
+        _ => None,
+    }
+}
+
+/// Whether the input format names the journal export format, which is read with
+/// [`JournalExportReader`] instead of line by line.
+pub fn is_export_format(format: &str) -> bool {
+    matches!(format.to_lowercase().as_str(), "journal-export" | "export")
+}
+
+/// Reads `journalctl -o export` streams and yields each entry as a `journalctl -o json`
+/// style JSON object.
+///
+/// Entries are separated by an empty line. Fields are `KEY=value` lines, or, for binary
+/// data, the key on its own line followed by a 64-bit little-endian length, the raw bytes
+/// and a newline.
+pub struct JournalExportReader<R> {
+    reader: R,
+    done: bool,
+}
+
+impl<R: BufRead> JournalExportReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            done: false,
+        }
+    }
+
+    fn read_entry(&mut self) -> io::Result<Option<Map<String, Value>>> {
+        let mut entry = Map::new();
+        let mut line = Vec::new();
+
+        loop {
+            line.clear();
+            if self.reader.read_until(b'\n', &mut line)? == 0 {
+                self.done = true;
+                break;
+            }
+            if line.last() == Some(&b'\n') {
+                line.pop();
+            }
+            if line.is_empty() {
+                if entry.is_empty() {
+                    continue;
+                }
+                break;
+            }
+
+            let (key, value) = match line.iter().position(|&b| b == b'=') {
+                Some(separator) => (&line[..separator], line[separator + 1..].to_vec()),
+                None => (&line[..], self.read_binary_value()?),
+            };
+            let key = String::from_utf8_lossy(key).into_owned();
+            let value = Value::String(String::from_utf8_lossy(&value).into_owned());
+
+            // Repeated fields become arrays, as in `journalctl -o json`
+            match entry.get_mut(&key) {
+                Some(Value::Array(values)) => values.push(value),
+                Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+                None => {
+                    entry.insert(key, value);
+                }
+            }
+        }
+
+        Ok(if entry.is_empty() { None } else { Some(entry) })
+    }
+
+    fn read_binary_value(&mut self) -> io::Result<Vec<u8>> {
+        let mut length = [0u8; 8];
+        self.reader.read_exact(&mut length)?;
+        let length = u64::from_le_bytes(length);
+
+        let mut value = Vec::new();
+        (&mut self.reader).take(length).read_to_end(&mut value)?;
+        if (value.len() as u64) < length {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "truncated binary field in journal export",
+            ));
+        }
+
+        // Binary values are terminated by a newline that is not part of the data
+        let mut newline = [0u8; 1];
+        self.reader.read_exact(&mut newline)?;
+        Ok(value)
+    }
+}
+
+impl<R: BufRead> Iterator for JournalExportReader<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.read_entry() {
+            Ok(Some(entry)) => Some(Ok(Value::Object(entry).to_string())),
+            Ok(None) => None,
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use crate::core::{FieldValue, Fields, LogEntry, Result, SourceTimezone, TimestampParser};
+use crate::parsers::{KeyMapping, Parser};
+use serde_json::{Map, Value};
+
+/// Parser for JSON Lines application logs (one JSON object per line).
+///
+/// Nested objects are flattened into dotted field names (`{"http":{"status":200}}` becomes
+/// `http.status`). Lines that are not JSON objects are kept as raw entries.
+pub struct JsonParser {
+    mapping: KeyMapping,
+    timestamp_parser: TimestampParser,
+}
+
+impl JsonParser {
+    pub fn new() -> Self {
+        Self::with_mapping(KeyMapping::default())
+    }
+
+    pub fn with_mapping(mapping: KeyMapping) -> Self {
+        Self {
+            mapping,
+            timestamp_parser: TimestampParser::default(),
+        }
+    }
+
+    pub fn with_timezone(mut self, timezone: SourceTimezone) -> Self {
+        self.timestamp_parser = TimestampParser::new(timezone);
+        self
+    }
+}
+
+impl Default for JsonParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for JsonParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let object = match serde_json::from_str::<Value>(line) {
+            Ok(Value::Object(object)) => object,
+            // Not a JSON object: keep the line as a raw entry
+            _ => return Ok(Some(LogEntry::new(line.to_string()))),
+        };
+
+        let mut fields = Fields::new();
+        flatten_object("", object, &mut fields);
+
+        let entry = LogEntry::new(line.to_string());
+
+        Ok(Some(self.mapping.apply(entry, fields, &self.timestamp_parser)))
+    }
+
+    fn name(&self) -> &'static str {
+        "json"
+    }
+}
+
+pub(crate) fn flatten_object(prefix: &str, object: Map<String, Value>, fields: &mut Fields) {
+    for (key, value) in object {
+        let key = if prefix.is_empty() {
+            key
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+
+        match value {
+            Value::Object(nested) => flatten_object(&key, nested, fields),
+            Value::Null => {}
+            Value::Bool(b) => {
+                fields.insert(key, FieldValue::Bool(b));
+            }
+            Value::Number(n) => {
+                let value = match n.as_i64() {
+                    Some(i) => FieldValue::Int(i),
+                    None => FieldValue::Float(n.as_f64().unwrap_or_default()),
+                };
+                fields.insert(key, value);
+            }
+            Value::String(s) => {
+                fields.insert(key, FieldValue::String(s));
+            }
+            // Arrays are kept as their JSON text
+            array @ Value::Array(_) => {
+                fields.insert(key, FieldValue::String(array.to_string()));
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::core::{FieldValue, Fields, LogEntry, Result, SourceTimezone, TimestampParser};
+use crate::parsers::{KeyMapping, Parser};
+
+/// Parser for logfmt lines: `time=2024-01-01T12:00:00Z level=warn msg="slow query" duration=1.2s`.
+///
+/// Unquoted values are typed (numbers, booleans, IPs, durations), quoted values stay strings
+/// and bare keys without `=` become `true`.
+pub struct LogfmtParser {
+    mapping: KeyMapping,
+    timestamp_parser: TimestampParser,
+}
+
+impl LogfmtParser {
+    pub fn new() -> Self {
+        Self::with_mapping(KeyMapping::default())
+    }
+
+    pub fn with_mapping(mapping: KeyMapping) -> Self {
+        Self {
+            mapping,
+            timestamp_parser: TimestampParser::default(),
+        }
+    }
+
+    pub fn with_timezone(mut self, timezone: SourceTimezone) -> Self {
+        self.timestamp_parser = TimestampParser::new(timezone);
+        self
+    }
+}
+
+impl Default for LogfmtParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for LogfmtParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let entry = LogEntry::new(line.to_string());
+
+        // A line without a single key=value pair is not logfmt: keep it as a raw entry
+        let fields = match parse_pairs(line) {
+            Some(fields) => fields,
+            None => return Ok(Some(entry)),
+        };
+
+        Ok(Some(self.mapping.apply(entry, fields, &self.timestamp_parser)))
+    }
+
+    fn name(&self) -> &'static str {
+        "logfmt"
+    }
+}
+
+// Split a logfmt line into typed fields; returns None if no `key=value` pair is present
+fn parse_pairs(line: &str) -> Option<Fields> {
+    let mut fields = Fields::new();
+    let mut has_pair = false;
+    let mut chars = line.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let mut key = String::new();
+        while let Some(c) = chars.next_if(|&c| c != '=' && !c.is_whitespace()) {
+            key.push(c);
+        }
+
+        if chars.next_if_eq(&'=').is_none() {
+            // Bare key
+            if !key.is_empty() {
+                fields.insert(key, FieldValue::Bool(true));
+            }
+            continue;
+        }
+        has_pair = true;
+
+        let value = if chars.next_if_eq(&'"').is_some() {
+            let mut value = String::new();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => break,
+                    '\\' => match chars.next() {
+                        Some('n') => value.push('\n'),
+                        Some('t') => value.push('\t'),
+                        Some('r') => value.push('\r'),
+                        Some(escaped) => value.push(escaped),
+                        None => value.push('\\'),
+                    },
+                    c => value.push(c),
+                }
+            }
+            FieldValue::String(value)
+        } else {
+            let mut value = String::new();
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                value.push(c);
+            }
+            FieldValue::infer(&value)
+        };
+
+        if !key.is_empty() {
+            fields.insert(key, value);
+        }
+    }
+
+    has_pair.then_some(fields)
+}
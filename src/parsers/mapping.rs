@@ -0,0 +1,81 @@
+use crate::config::Config;
+use crate::core::{FieldValue, Fields, LogEntry, LogLevel, TimestampParser};
+
+/// Which keys of a structured record map onto `LogEntry.timestamp`, `level` and `message`.
+///
+/// Each list is tried in order and the first key present in the record wins.
+#[derive(Debug, Clone)]
+pub struct KeyMapping {
+    pub timestamp: Vec<String>,
+    pub level: Vec<String>,
+    pub message: Vec<String>,
+}
+
+impl KeyMapping {
+    /// Build the default mapping, with any keys given in the config tried first.
+    pub fn from_config(config: &Config) -> Self {
+        let mut mapping = Self::default();
+        if let Some(key) = &config.timestamp_key {
+            mapping.timestamp.insert(0, key.clone());
+        }
+        if let Some(key) = &config.level_key {
+            mapping.level.insert(0, key.clone());
+        }
+        if let Some(key) = &config.message_key {
+            mapping.message.insert(0, key.clone());
+        }
+        mapping
+    }
+
+    /// Move the mapped timestamp, level and message out of `fields` into `entry` and keep
+    /// the remaining fields. Values that do not parse as a timestamp or level stay fields.
+    pub fn apply(
+        &self,
+        mut entry: LogEntry,
+        mut fields: Fields,
+        timestamp_parser: &TimestampParser,
+    ) -> LogEntry {
+        if let Some((key, value)) = take_first(&mut fields, &self.timestamp) {
+            match timestamp_parser.parse_value(&value) {
+                Some(timestamp) => entry = entry.with_timestamp(timestamp),
+                None => {
+                    fields.insert(key, value);
+                }
+            }
+        }
+
+        if let Some((key, value)) = take_first(&mut fields, &self.level) {
+            match value.to_string().parse::<LogLevel>() {
+                Ok(level) => entry = entry.with_level(level),
+                Err(_) => {
+                    fields.insert(key, value);
+                }
+            }
+        }
+
+        if let Some((_, value)) = take_first(&mut fields, &self.message) {
+            entry = entry.with_message(value.to_string());
+        }
+
+        entry.fields = fields;
+        entry
+    }
+}
+
+impl Default for KeyMapping {
+    fn default() -> Self {
+        let keys = |keys: &[&str]| keys.iter().map(|key| key.to_string()).collect();
+
+        Self {
+            timestamp: keys(&["timestamp", "@timestamp", "time", "ts", "datetime", "date"]),
+            level: keys(&["level", "lvl", "severity", "loglevel", "log.level"]),
+            message: keys(&["message", "msg", "@message", "log"]),
+        }
+    }
+}
+
+// Remove and return the first field whose key appears in `keys`, together with that key
+fn take_first(fields: &mut Fields, keys: &[String]) -> Option<(String, FieldValue)> {
+    keys.iter()
+        .find_map(|key| fields.remove_entry(key.as_str()))
+}
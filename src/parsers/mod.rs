@@ -1,11 +1,192 @@
-use crate::core::{LogEntry, Result};
+use crate::config::Config;
+use crate::core::{LogEntry, LogParserError, Result};
 
 pub trait Parser {
     fn parse_line(&self, line: &str) -> Result<Option<LogEntry>>;
     fn name(&self) -> &'static str;
+
+    /// Parse the next line of a stream, numbered by `number`; entries are returned with the
+    /// number of their first line. Parsers that join several lines into one entry (partial
+    /// container log lines, ...) override this together with `finish`.
+    fn feed_line(&mut self, line: &str, number: usize) -> Result<Option<(usize, LogEntry)>> {
+        Ok(self.parse_line(line)?.map(|entry| (number, entry)))
+    }
+
+    /// Flush an entry still buffered at end of input; called until it returns `Ok(None)`.
+    fn finish(&mut self) -> Result<Option<(usize, LogEntry)>> {
+        Ok(None)
+    }
 }
 
 // Parser implementations will be added in subsequent phases
+pub mod access;
+pub mod aws;
+pub mod container;
+pub mod csv;
+pub mod custom;
+pub mod detect;
+pub mod error_log;
+pub mod gelf;
+pub mod grok;
+pub mod journal;
+pub mod json;
+pub mod logfmt;
+pub mod mapping;
+pub mod mysql;
+pub mod postgres;
+pub mod proxy;
+pub mod security;
+pub mod syslog;
 pub mod text;
+pub mod w3c;
 
+pub use access::AccessLogParser;
+pub use aws::AwsParser;
+pub use container::{CriParser, DockerParser};
+pub use self::csv::CsvParser;
+pub use custom::CustomParser;
+pub use detect::{detect_parser, Detection};
+pub use error_log::ErrorLogParser;
+pub use gelf::GelfParser;
+pub use grok::Grok;
+pub use journal::{JournalExportReader, JournalParser};
+pub use json::JsonParser;
+pub use logfmt::LogfmtParser;
+pub use mapping::KeyMapping;
+pub use mysql::{MysqlErrorLogParser, MysqlSlowLogParser};
+pub use postgres::PostgresParser;
+pub use proxy::{EnvoyParser, HaproxyParser};
+pub use security::{CefParser, LeefParser};
+pub use syslog::SyslogParser;
 pub use text::TextParser;
+pub use w3c::W3cParser;
+
+// Built-in formats tried by automatic detection, most specific first
+pub const DETECTABLE_FORMATS: &[&str] = &[
+    "access",
+    "error",
+    "cef",
+    "leef",
+    "syslog",
+    "alb",
+    "elb",
+    "s3",
+    "w3c",
+    "cloudfront",
+    "haproxy",
+    "envoy",
+    "postgres",
+    "mysql",
+    "mysql-slow",
+    "docker",
+    "cri",
+    "journal",
+    "gelf",
+    "json",
+    "logfmt",
+    "csv",
+    "tsv",
+];
+
+// Create a parser from the input format name given on the command line or in the config
+pub fn create_parser(format: &str, config: &Config) -> Result<Box<dyn Parser>> {
+    let timezone = config.timezone;
+
+    match format.to_lowercase().as_str() {
+        "text" => Ok(Box::new(TextParser::new()?.with_timezone(timezone))),
+        "access" | "apache" | "nginx" | "combined" | "common" => {
+            Ok(Box::new(AccessLogParser::new()?))
+        }
+        "error" | "error-log" | "nginx-error" | "apache-error" => {
+            Ok(Box::new(ErrorLogParser::new()?.with_timezone(timezone)))
+        }
+        "gelf" => Ok(Box::new(GelfParser::new())),
+        "json" | "jsonl" | "ndjson" => Ok(Box::new(
+            JsonParser::with_mapping(KeyMapping::from_config(config)).with_timezone(timezone),
+        )),
+        "csv" => Ok(Box::new(
+            CsvParser::csv(KeyMapping::from_config(config)).with_timezone(timezone),
+        )),
+        "tsv" => Ok(Box::new(
+            CsvParser::tsv(KeyMapping::from_config(config)).with_timezone(timezone),
+        )),
+        "logfmt" => Ok(Box::new(
+            LogfmtParser::with_mapping(KeyMapping::from_config(config)).with_timezone(timezone),
+        )),
+        "syslog" => Ok(Box::new(SyslogParser::new()?.with_timezone(timezone))),
+        "cef" => Ok(Box::new(CefParser::new()?.with_timezone(timezone))),
+        "leef" => Ok(Box::new(LeefParser::new()?.with_timezone(timezone))),
+        "alb" => Ok(Box::new(AwsParser::alb())),
+        "elb" => Ok(Box::new(AwsParser::elb())),
+        "s3" => Ok(Box::new(AwsParser::s3())),
+        "cloudfront" => Ok(Box::new(W3cParser::cloudfront().with_timezone(timezone))),
+        "w3c" | "iis" => Ok(Box::new(W3cParser::new().with_timezone(timezone))),
+        "haproxy" => Ok(Box::new(HaproxyParser::new()?.with_timezone(timezone))),
+        "envoy" => Ok(Box::new(EnvoyParser::new()?)),
+        "postgres" | "postgresql" => Ok(Box::new(
+            PostgresParser::new(
+                config
+                    .log_line_prefix
+                    .as_deref()
+                    .unwrap_or(postgres::DEFAULT_LOG_LINE_PREFIX),
+            )?
+            .with_timezone(timezone),
+        )),
+        "mysql" | "mysql-error" | "mariadb" => {
+            Ok(Box::new(MysqlErrorLogParser::new()?.with_timezone(timezone)))
+        }
+        "mysql-slow" | "slow-query" => {
+            Ok(Box::new(MysqlSlowLogParser::new()?.with_timezone(timezone)))
+        }
+        "journal" | "journald" | "journal-json" | "journal-export" | "export" => {
+            Ok(Box::new(JournalParser::new()))
+        }
+        "docker" => Ok(Box::new(DockerParser::new(create_inner_parser(config)?))),
+        "cri" | "containerd" | "crio" => {
+            Ok(Box::new(CriParser::new(create_inner_parser(config)?)?))
+        }
+        "grok" => match &config.grok_pattern {
+            Some(expression) => Ok(Box::new(
+                Grok::with_pattern_files(&config.grok_pattern_files)?
+                    .compile(expression)?
+                    .with_timezone(timezone),
+            )),
+            None => Err(LogParserError::Config {
+                message: "The grok input format requires a grok expression".to_string(),
+            }),
+        },
+        // User-defined formats from the config file
+        _ => match config.formats.get(format) {
+            Some(definition) => Ok(Box::new(
+                CustomParser::new(
+                    format,
+                    definition,
+                    &Grok::with_pattern_files(&config.grok_pattern_files)?,
+                )?
+                .with_timezone(timezone),
+            )),
+            None => Err(LogParserError::Config {
+                message: format!("Unsupported input format: {}", format),
+            }),
+        },
+    }
+}
+
+// Parser for the application lines inside container logs, if the user chose one
+fn create_inner_parser(config: &Config) -> Result<Option<Box<dyn Parser>>> {
+    match config.inner_format.as_deref() {
+        // Names are matched case-insensitively, like in `create_parser`
+        Some(format)
+            if matches!(
+                format.to_lowercase().as_str(),
+                "docker" | "cri" | "containerd" | "crio"
+            ) =>
+        {
+            Err(LogParserError::Config {
+                message: format!("Container formats cannot be nested: {}", format),
+            })
+        }
+        Some(format) => create_parser(format, config).map(Some),
+        None => Ok(None),
+    }
+}
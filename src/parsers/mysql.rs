@@ -0,0 +1,296 @@
+use crate::core::{FieldValue, LogEntry, LogLevel, Result, SourceTimezone, TimestampParser};
+use crate::parsers::Parser;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use std::net::IpAddr;
+
+/// Parser for the MySQL and MariaDB error log.
+///
+/// MySQL 8.0: `2024-01-01T12:00:00.123456Z 8 [Warning] [MY-010055] [Server] IP address ...`;
+/// 5.7 and MariaDB omit the error code and subsystem, 5.5 and older write
+/// `240101 12:00:00 [ERROR] ...` without a thread id.
+pub struct MysqlErrorLogParser {
+    line_regex: Regex,
+    timestamp_parser: TimestampParser,
+}
+
+impl MysqlErrorLogParser {
+    pub fn new() -> Result<Self> {
+        let line_regex = Regex::new(
+            r"^(?:(?P<time>\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:\d{2})?)\s+(?P<thread>\d+)|(?P<old_time>\d{6} +\d{1,2}:\d{2}:\d{2})) \[(?P<level>System|Note|Warning|Error|ERROR)\](?: \[(?P<error_code>MY-\d+)\])?(?: \[(?P<subsystem>\w+)\])? (?P<message>.*)$",
+        )?;
+
+        Ok(Self {
+            line_regex,
+            timestamp_parser: TimestampParser::default(),
+        })
+    }
+
+    pub fn with_timezone(mut self, timezone: SourceTimezone) -> Self {
+        self.timestamp_parser = TimestampParser::new(timezone);
+        self
+    }
+}
+
+impl Default for MysqlErrorLogParser {
+    fn default() -> Self {
+        Self::new().expect("Failed to create default MysqlErrorLogParser")
+    }
+}
+
+impl Parser for MysqlErrorLogParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let captures = match self.line_regex.captures(line) {
+            Some(captures) => captures,
+            None => return Ok(Some(LogEntry::new(line.to_string()))),
+        };
+
+        let mut entry =
+            LogEntry::new(line.to_string()).with_message(captures["message"].to_string());
+
+        let timestamp = match (captures.name("time"), captures.name("old_time")) {
+            (Some(time), _) => self.timestamp_parser.parse(time.as_str()),
+            (_, Some(time)) => parse_old_time(&self.timestamp_parser, time.as_str()),
+            _ => None,
+        };
+        if let Some(timestamp) = timestamp {
+            entry = entry.with_timestamp(timestamp);
+        }
+
+        if let Some(thread) = captures
+            .name("thread")
+            .and_then(|t| t.as_str().parse::<i64>().ok())
+        {
+            entry = entry.with_field("thread_id", thread);
+        }
+        for name in ["error_code", "subsystem"] {
+            if let Some(value) = captures.name(name) {
+                entry = entry.with_field(name, value.as_str());
+            }
+        }
+
+        let level = match &captures["level"] {
+            "System" => LogLevel::Notice,
+            "Note" => LogLevel::Info,
+            "Warning" => LogLevel::Warn,
+            _ => LogLevel::Error,
+        };
+
+        Ok(Some(entry.with_level(level)))
+    }
+
+    fn name(&self) -> &'static str {
+        "mysql"
+    }
+}
+
+/// Parser for the MySQL and MariaDB slow query log.
+///
+/// Each record is a block of `# Time:`, `# User@Host:` and `# Query_time:` comment lines
+/// followed by the statement, which may span several lines:
+///
+/// ```text
+/// # Time: 2024-01-01T12:00:00.123456Z
+/// # User@Host: app[app] @ web1 [10.0.0.5]  Id:    42
+/// # Query_time: 2.501234  Lock_time: 0.000123 Rows_sent: 1  Rows_examined: 1000000
+/// use shop;
+/// SET timestamp=1704110400;
+/// SELECT * FROM orders WHERE note LIKE '%late%';
+/// ```
+///
+/// The statement becomes the message; `Query_time`, `Lock_time`, `Rows_sent`,
+/// `Rows_examined` and any other `Name: value` statistics (MariaDB and Percona add more)
+/// become numeric fields under their own names.
+pub struct MysqlSlowLogParser {
+    user_host_regex: Regex,
+    statistic_regex: Regex,
+    timestamp_parser: TimestampParser,
+    pending: Option<SlowQuery>,
+}
+
+// A record being collected
+struct SlowQuery {
+    entry: LogEntry,
+    statement: Vec<String>,
+    // Number of the line the record starts at
+    first_line: usize,
+}
+
+impl SlowQuery {
+    fn new(first_line: usize) -> Self {
+        Self {
+            entry: LogEntry::new(String::new()),
+            statement: Vec::new(),
+            first_line,
+        }
+    }
+}
+
+impl MysqlSlowLogParser {
+    pub fn new() -> Result<Self> {
+        let user_host_regex = Regex::new(
+            r"^(?P<user>[^\[\s]*)\[[^\]]*\] @ (?P<host>\S*) \[(?P<ip>[^\]]*)\](?:\s+Id:\s+(?P<id>\d+))?",
+        )?;
+        let statistic_regex = Regex::new(r"(\w+): (\S+)")?;
+
+        Ok(Self {
+            user_host_regex,
+            statistic_regex,
+            timestamp_parser: TimestampParser::default(),
+            pending: None,
+        })
+    }
+
+    pub fn with_timezone(mut self, timezone: SourceTimezone) -> Self {
+        self.timestamp_parser = TimestampParser::new(timezone);
+        self
+    }
+
+    fn apply_header(&self, mut entry: LogEntry, header: &str) -> LogEntry {
+        if let Some(time) = header.strip_prefix("Time:") {
+            let time = time.trim();
+            let timestamp = self
+                .timestamp_parser
+                .parse(time)
+                .or_else(|| parse_old_time(&self.timestamp_parser, time));
+            if let Some(timestamp) = timestamp {
+                entry = entry.with_timestamp(timestamp);
+            }
+        } else if let Some(user_host) = header.strip_prefix("User@Host:") {
+            if let Some(captures) = self.user_host_regex.captures(user_host.trim()) {
+                for name in ["user", "host"] {
+                    if !captures[name].is_empty() {
+                        entry = entry.with_field(name, &captures[name]);
+                    }
+                }
+                if let Ok(ip) = captures["ip"].parse::<IpAddr>() {
+                    entry = entry.with_field("client_ip", ip);
+                }
+                if let Some(id) = captures
+                    .name("id")
+                    .and_then(|id| id.as_str().parse::<i64>().ok())
+                {
+                    entry = entry.with_field("thread_id", id);
+                }
+            }
+        } else {
+            for captures in self.statistic_regex.captures_iter(header) {
+                entry = entry.with_field(&captures[1], FieldValue::infer(&captures[2]));
+            }
+        }
+        entry
+    }
+
+    fn complete(record: SlowQuery) -> (usize, LogEntry) {
+        let statement = record.statement.join("\n");
+        (record.first_line, record.entry.with_message(statement))
+    }
+}
+
+impl Default for MysqlSlowLogParser {
+    fn default() -> Self {
+        Self::new().expect("Failed to create default MysqlSlowLogParser")
+    }
+}
+
+impl Parser for MysqlSlowLogParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() || is_server_header(line) {
+            return Ok(None);
+        }
+
+        let entry = LogEntry::new(line.to_string());
+        match line.strip_prefix("# ") {
+            Some(header) => Ok(Some(self.apply_header(entry, header))),
+            None => Ok(Some(entry)),
+        }
+    }
+
+    fn feed_line(&mut self, line: &str, number: usize) -> Result<Option<(usize, LogEntry)>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+        // Written again on every server restart
+        if is_server_header(line) {
+            return Ok(self.pending.take().map(Self::complete));
+        }
+
+        if let Some(header) = line.strip_prefix("# ") {
+            // A comment line after the statement starts the next record
+            let (mut record, completed) = match self.pending.take() {
+                Some(record) if !record.statement.is_empty() => {
+                    (SlowQuery::new(number), Some(Self::complete(record)))
+                }
+                Some(record) => (record, None),
+                None => (SlowQuery::new(number), None),
+            };
+
+            if !record.entry.raw_line.is_empty() {
+                record.entry.raw_line.push('\n');
+            }
+            record.entry.raw_line.push_str(line);
+            record.entry = self.apply_header(record.entry, header);
+
+            self.pending = Some(record);
+            return Ok(completed);
+        }
+
+        let record = match self.pending.as_mut() {
+            Some(record) => record,
+            None => return Ok(Some((number, LogEntry::new(line.to_string())))),
+        };
+        record.entry.raw_line.push('\n');
+        record.entry.raw_line.push_str(line);
+
+        if record.statement.is_empty() {
+            let lower = line.to_ascii_lowercase();
+            if let Some(database) = lower.strip_prefix("use ") {
+                let database = line[line.len() - database.len()..].trim_end_matches(';');
+                record.entry.fields.insert(
+                    "database".to_string(),
+                    FieldValue::from(database.trim_matches('`')),
+                );
+                return Ok(None);
+            }
+            if let Some(epoch) = lower.strip_prefix("set timestamp=") {
+                // The start of the statement; `# Time:` is missing when several slow
+                // queries end within the same second
+                if record.entry.timestamp.is_none() {
+                    record.entry.timestamp = epoch
+                        .trim_end_matches(';')
+                        .parse()
+                        .ok()
+                        .and_then(|secs| DateTime::from_timestamp(secs, 0));
+                }
+                return Ok(None);
+            }
+        }
+        record.statement.push(line.to_string());
+        Ok(None)
+    }
+
+    fn finish(&mut self) -> Result<Option<(usize, LogEntry)>> {
+        Ok(self.pending.take().map(Self::complete))
+    }
+
+    fn name(&self) -> &'static str {
+        "mysql-slow"
+    }
+}
+
+// `240101 12:00:00` (or `240101  2:00:00`) as written by MySQL 5.6 and older
+fn parse_old_time(timestamp_parser: &TimestampParser, time: &str) -> Option<DateTime<Utc>> {
+    let time = time.split_whitespace().collect::<Vec<_>>().join(" ");
+    timestamp_parser.parse_with_format(&time, "%y%m%d %H:%M:%S")
+}
+
+// The banner mysqld writes at the top of the slow log when it starts
+fn is_server_header(line: &str) -> bool {
+    line.starts_with("Tcp port:")
+        || (line.starts_with("Time ") && line.contains("Id Command"))
+        || (line.contains(", Version: ") && line.trim_end().ends_with("started with:"))
+}
@@ -0,0 +1,316 @@
+use crate::core::{
+    parse_epoch, FieldValue, LogEntry, LogLevel, Result, SourceTimezone, TimestampParser,
+};
+use crate::parsers::w3c::seconds_to_duration;
+use crate::parsers::Parser;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+/// `log_line_prefix` of PostgreSQL 10 and later.
+pub const DEFAULT_LOG_LINE_PREFIX: &str = "%m [%p] ";
+
+const SEVERITIES: &str =
+    "DEBUG[1-5]|INFO|NOTICE|WARNING|ERROR|LOG|FATAL|PANIC|DETAIL|HINT|QUERY|CONTEXT|LOCATION|STATEMENT";
+
+// Severities that belong to the preceding message rather than starting a new one
+const CONTINUATIONS: &[&str] = &[
+    "DETAIL",
+    "HINT",
+    "QUERY",
+    "CONTEXT",
+    "LOCATION",
+    "STATEMENT",
+];
+
+/// Parser for PostgreSQL's stderr log (`log_destination = 'stderr'`, also what the logging
+/// collector writes).
+///
+/// The prefix is described with the server's `log_line_prefix` escapes (`%m [%p] ` by
+/// default); `%u`, `%d`, `%a`, `%h`, `%e`, ... become fields. `DETAIL:`, `HINT:`,
+/// `STATEMENT:`, ... lines and the continuation lines of multi-line statements are joined
+/// onto the message they belong to as `detail`, `hint`, `statement`, ... fields.
+pub struct PostgresParser {
+    line_regex: Regex,
+    duration_regex: Regex,
+    timestamp_parser: TimestampParser,
+    // The entry being joined and the number of its first line
+    pending: Option<(usize, LogEntry)>,
+    // Field the last joined line went to; `None` for the message itself
+    last_part: Option<String>,
+}
+
+impl PostgresParser {
+    pub fn new(log_line_prefix: &str) -> Result<Self> {
+        let line_regex = Regex::new(&format!(
+            r"^{}(?P<severity>{}):\s+(?P<message>.*)$",
+            prefix_pattern(log_line_prefix),
+            SEVERITIES
+        ))?;
+        let duration_regex = Regex::new(r"^duration: (\d+(?:\.\d+)?) ms")?;
+
+        Ok(Self {
+            line_regex,
+            duration_regex,
+            timestamp_parser: TimestampParser::default(),
+            pending: None,
+            last_part: None,
+        })
+    }
+
+    pub fn with_timezone(mut self, timezone: SourceTimezone) -> Self {
+        self.timestamp_parser = TimestampParser::new(timezone);
+        self
+    }
+
+    fn parse_prefixed(&self, line: &str) -> Option<(String, LogEntry)> {
+        let captures = self.line_regex.captures(line)?;
+        let severity = captures["severity"].to_string();
+        let message = &captures["message"];
+        let mut entry = LogEntry::new(line.to_string()).with_message(message.to_string());
+
+        if let Some(time) = captures.name("time") {
+            if let Some(timestamp) = self.parse_timestamp(time.as_str()) {
+                entry = entry.with_timestamp(timestamp);
+            }
+        } else if let Some(epoch) = captures.name("epoch") {
+            if let Some(timestamp) = epoch.as_str().parse().ok().and_then(parse_epoch) {
+                entry = entry.with_timestamp(timestamp);
+            }
+        }
+        if let Some(start) = captures.name("session_start") {
+            if let Some(timestamp) = self.parse_timestamp(start.as_str()) {
+                entry = entry.with_field("session_start", timestamp);
+            }
+        }
+
+        for name in [
+            "application_name",
+            "user",
+            "database",
+            "backend_type",
+            "command_tag",
+            "sql_state",
+            "session_id",
+            "virtual_xid",
+        ] {
+            if let Some(value) = captures.name(name) {
+                if !matches!(value.as_str(), "" | "[unknown]") {
+                    entry = entry.with_field(name, value.as_str());
+                }
+            }
+        }
+        for name in ["pid", "leader_pid", "session_line", "xid", "query_id"] {
+            if let Some(value) = captures
+                .name(name)
+                .and_then(|v| v.as_str().parse::<i64>().ok())
+            {
+                entry = entry.with_field(name, value);
+            }
+        }
+
+        // %r is `host(port)`, %h just the host; `[local]` for Unix socket connections
+        if let Some(remote) = captures
+            .name("remote")
+            .or_else(|| captures.name("remote_host"))
+        {
+            let (host, port) = match remote.as_str().split_once('(') {
+                Some((host, port)) => (host, port.trim_end_matches(')').parse::<i64>().ok()),
+                None => (remote.as_str(), None),
+            };
+            entry = match host.parse::<IpAddr>() {
+                Ok(ip) => entry.with_field("client_ip", ip),
+                Err(_) if !host.is_empty() => entry.with_field("remote_host", host),
+                Err(_) => entry,
+            };
+            if let Some(port) = port {
+                entry = entry.with_field("client_port", port);
+            }
+        }
+
+        if let Some(level) = level_from_severity(&severity) {
+            entry = entry.with_level(level);
+        }
+        if let Some(millis) = self.duration_regex.captures(message) {
+            if let Some(duration) = millis[1]
+                .parse::<f64>()
+                .ok()
+                .and_then(|millis| seconds_to_duration(millis / 1000.0))
+            {
+                entry = entry.with_field("duration", duration);
+            }
+        }
+
+        entry = entry.with_field("severity", severity.as_str());
+        Some((severity, entry))
+    }
+
+    // `%t` and `%m` end with the server's zone abbreviation (`UTC`, `JST`, `+09`, ...)
+    fn parse_timestamp(&self, time: &str) -> Option<DateTime<Utc>> {
+        if let Some((naive, zone)) = time.rsplit_once(' ') {
+            if let Ok(timezone) = zone.parse::<SourceTimezone>() {
+                return TimestampParser::new(timezone).parse(naive);
+            }
+            return self.timestamp_parser.parse(naive);
+        }
+        self.timestamp_parser.parse(time)
+    }
+
+    fn append(&mut self, part: Option<String>, text: &str, raw: &str) {
+        let entry = match self.pending.as_mut() {
+            Some((_, entry)) => entry,
+            None => return,
+        };
+        entry.raw_line.push('\n');
+        entry.raw_line.push_str(raw);
+
+        match &part {
+            Some(key) => match entry.fields.get_mut(key) {
+                Some(FieldValue::String(value)) => {
+                    value.push('\n');
+                    value.push_str(text);
+                }
+                _ => {
+                    entry.fields.insert(key.clone(), FieldValue::from(text));
+                }
+            },
+            None => {
+                entry.message.push('\n');
+                entry.message.push_str(text);
+            }
+        }
+        self.last_part = part;
+    }
+}
+
+impl Default for PostgresParser {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOG_LINE_PREFIX).expect("Failed to create default PostgresParser")
+    }
+}
+
+impl Parser for PostgresParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        match self.parse_prefixed(line) {
+            Some((_, entry)) => Ok(Some(entry)),
+            None => Ok(Some(LogEntry::new(line.to_string()))),
+        }
+    }
+
+    fn feed_line(&mut self, line: &str, number: usize) -> Result<Option<(usize, LogEntry)>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        match self.parse_prefixed(line) {
+            Some((severity, entry))
+                if CONTINUATIONS.contains(&severity.as_str()) && self.pending.is_some() =>
+            {
+                self.append(Some(severity.to_lowercase()), &entry.message, line);
+                Ok(None)
+            }
+            Some((_, entry)) => {
+                self.last_part = None;
+                Ok(self.pending.replace((number, entry)))
+            }
+            // Unprefixed lines continue a multi-line message or statement
+            None if self.pending.is_some() => {
+                self.append(self.last_part.clone(), line, line);
+                Ok(None)
+            }
+            None => Ok(Some((number, LogEntry::new(line.to_string())))),
+        }
+    }
+
+    fn finish(&mut self) -> Result<Option<(usize, LogEntry)>> {
+        Ok(self.pending.take())
+    }
+
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+}
+
+fn level_from_severity(severity: &str) -> Option<LogLevel> {
+    match severity {
+        s if s.starts_with("DEBUG") => Some(LogLevel::Debug),
+        "INFO" | "LOG" => Some(LogLevel::Info),
+        "NOTICE" => Some(LogLevel::Notice),
+        "WARNING" => Some(LogLevel::Warn),
+        "ERROR" => Some(LogLevel::Error),
+        "FATAL" => Some(LogLevel::Fatal),
+        // PANIC takes the whole server down
+        "PANIC" => Some(LogLevel::Emergency),
+        _ => None,
+    }
+}
+
+// Translate `log_line_prefix` escapes into a regex; see "log_line_prefix" in the
+// PostgreSQL documentation for the list
+fn prefix_pattern(prefix: &str) -> String {
+    const TIME: &str = r"\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}(?:\.\d+)? \S+";
+
+    let mut pattern = String::new();
+    let mut seen = HashSet::new();
+    let mut optional_tail = false;
+    let mut chars = prefix.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            pattern.push_str(&regex::escape(&c.to_string()));
+            continue;
+        }
+
+        // Padding such as `%-10u`
+        while chars.next_if(|c| *c == '-' || c.is_ascii_digit()).is_some() {}
+
+        let (name, value) = match chars.next() {
+            Some('a') => ("application_name", ".*?"),
+            Some('u') => ("user", ".*?"),
+            Some('d') => ("database", ".*?"),
+            Some('r') => ("remote", r"\S*?"),
+            Some('h') => ("remote_host", r"\S*?"),
+            Some('b') => ("backend_type", ".*?"),
+            Some('p') => ("pid", r"\d+"),
+            Some('P') => ("leader_pid", r"\d*"),
+            Some('t') | Some('m') => ("time", TIME),
+            Some('n') => ("epoch", r"\d+(?:\.\d+)?"),
+            Some('i') => ("command_tag", ".*?"),
+            Some('e') => ("sql_state", "[0-9A-Z]{5}"),
+            Some('c') => ("session_id", r"[0-9a-f]+\.[0-9a-f]+"),
+            Some('l') => ("session_line", r"\d+"),
+            Some('s') => ("session_start", TIME),
+            Some('v') => ("virtual_xid", r"\S*?"),
+            Some('x') => ("xid", r"\d+"),
+            Some('Q') => ("query_id", r"-?\d+"),
+            // Processes without a session (checkpointer, ...) stop the prefix at %q
+            Some('q') => {
+                pattern.push_str("(?:");
+                optional_tail = true;
+                continue;
+            }
+            Some(other) => {
+                pattern.push_str(&regex::escape(&other.to_string()));
+                continue;
+            }
+            None => break,
+        };
+
+        // A group name may only be used once in a regex
+        if seen.insert(name) {
+            pattern.push_str(&format!("(?P<{}>{})", name, value));
+        } else {
+            pattern.push_str(&format!("(?:{})", value));
+        }
+    }
+
+    if optional_tail {
+        pattern.push_str(")?");
+    }
+    pattern
+}
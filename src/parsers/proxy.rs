@@ -0,0 +1,296 @@
+use crate::core::{LogEntry, LogLevel, Result, SourceTimezone, TimestampParser};
+use crate::parsers::access::level_from_status;
+use crate::parsers::Parser;
+use regex::{Captures, Regex};
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// Parser for HAProxy's default HTTP log format (`option httplog`), with or without the
+/// syslog header:
+///
+/// `haproxy[14389]: 10.0.1.2:33317 [06/Feb/2009:12:14:14.655] http-in static/srv1 10/0/30/69/109
+/// 200 2750 - - ---- 1/1/1/1/0 0/0 {1wt.eu} {} "GET /index.html HTTP/1.1"`
+///
+/// The timers `Tq/Tw/Tc/Tr/Tt` become the duration fields `tq`, `tw`, `tc`, `tr` and `tt`
+/// (`-1`, an aborted phase, is left out). An abnormal termination state raises the level to
+/// at least warn.
+pub struct HaproxyParser {
+    line_regex: Regex,
+    timestamp_parser: TimestampParser,
+}
+
+impl HaproxyParser {
+    pub fn new() -> Result<Self> {
+        let line_regex = Regex::new(concat!(
+            r"^(?:.*?\]: )?",
+            r"(?P<client>\S+) \[(?P<accept_date>[^\]]+)\] (?P<frontend_name>\S+) ",
+            r"(?P<backend_name>[^/\s]+)/(?P<server_name>\S+) ",
+            r"(?P<tq>-?\d+)/(?P<tw>-?\d+)/(?P<tc>-?\d+)/(?P<tr>-?\d+)/\+?(?P<tt>-?\d+) ",
+            r"(?P<status_code>-?\d+) \+?(?P<bytes_read>\d+) ",
+            r"(?P<captured_request_cookie>\S+) (?P<captured_response_cookie>\S+) ",
+            r"(?P<termination_state>\S{4}) ",
+            r"(?P<actconn>\d+)/(?P<feconn>\d+)/(?P<beconn>\d+)/(?P<srv_conn>\d+)/\+?(?P<retries>\d+) ",
+            r"(?P<srv_queue>\d+)/(?P<backend_queue>\d+)",
+            r"(?: \{(?P<captured_request_headers>[^}]*)\})?(?: \{(?P<captured_response_headers>[^}]*)\})?",
+            r#" "(?P<request>[^"]*)"?"#,
+        ))?;
+
+        Ok(Self {
+            line_regex,
+            timestamp_parser: TimestampParser::default(),
+        })
+    }
+
+    /// Timezone of the accept date, which HAProxy logs without a zone.
+    pub fn with_timezone(mut self, timezone: SourceTimezone) -> Self {
+        self.timestamp_parser = TimestampParser::new(timezone);
+        self
+    }
+}
+
+impl Default for HaproxyParser {
+    fn default() -> Self {
+        Self::new().expect("Failed to create default HaproxyParser")
+    }
+}
+
+impl Parser for HaproxyParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let mut entry = LogEntry::new(line.to_string());
+
+        // Lines that are not HAProxy HTTP logs (TCP logs, startup messages) are kept as raw entries
+        let captures = match self.line_regex.captures(line) {
+            Some(captures) => captures,
+            None => return Ok(Some(entry)),
+        };
+
+        if let Some(timestamp) = self
+            .timestamp_parser
+            .parse_with_format(&captures["accept_date"], "%d/%b/%Y:%H:%M:%S%.f")
+        {
+            entry = entry.with_timestamp(timestamp);
+        }
+
+        entry = with_addr_port(entry, "client", &captures["client"]);
+
+        for name in [
+            "frontend_name",
+            "backend_name",
+            "server_name",
+            "captured_request_cookie",
+            "captured_response_cookie",
+            "termination_state",
+            "captured_request_headers",
+            "captured_response_headers",
+        ] {
+            if let Some(value) = captures.name(name) {
+                if !matches!(value.as_str(), "-" | "") {
+                    entry = entry.with_field(name, value.as_str());
+                }
+            }
+        }
+
+        for timer in ["tq", "tw", "tc", "tr", "tt"] {
+            if let Some(duration) = millis(&captures, timer) {
+                entry = entry.with_field(timer, duration);
+            }
+        }
+
+        for counter in [
+            "bytes_read",
+            "actconn",
+            "feconn",
+            "beconn",
+            "srv_conn",
+            "retries",
+            "srv_queue",
+            "backend_queue",
+        ] {
+            if let Ok(value) = captures[counter].parse::<i64>() {
+                entry = entry.with_field(counter, value);
+            }
+        }
+
+        let mut level = LogLevel::Info;
+        if let Ok(status) = captures["status_code"].parse::<i64>() {
+            entry = entry.with_field("status_code", status);
+            level = level_from_status(status);
+        }
+        // The first character of the termination state is the session end cause; `-` is normal
+        if !captures["termination_state"].starts_with('-') {
+            level = level.max(LogLevel::Warn);
+        }
+        entry = entry.with_level(level);
+
+        if let Some(request) = captures.name("request") {
+            entry = with_request(entry, request.as_str());
+        }
+
+        Ok(Some(entry))
+    }
+
+    fn name(&self) -> &'static str {
+        "haproxy"
+    }
+}
+
+/// Parser for Envoy's default access log format:
+///
+/// `[2016-04-15T20:17:00.310Z] "POST /api/v1/locations HTTP/2" 204 - 154 0 226 100 "10.0.35.28"
+/// "nsq2http" "cc21d9b0-cf5c-432b-8c7e-98aeb7988cd2" "locations" "tcp://10.0.2.1:80"`
+///
+/// The longer default of recent Envoy and Istio versions (response code details, upstream
+/// cluster, local and remote addresses, ...) is understood as well. `duration` and
+/// `upstream_service_time` are durations; response flags other than `-` raise the level to
+/// at least warn.
+pub struct EnvoyParser {
+    line_regex: Regex,
+    timestamp_parser: TimestampParser,
+}
+
+impl EnvoyParser {
+    pub fn new() -> Result<Self> {
+        let line_regex = Regex::new(concat!(
+            r#"^\[(?P<start_time>[^\]]+)\] "(?P<request>[^"]*)" "#,
+            r"(?P<response_code>\d+) (?P<response_flags>\S+) ",
+            r#"(?:(?P<response_code_details>\S+) (?P<connection_termination_details>\S+) "(?P<upstream_transport_failure_reason>[^"]*)" )?"#,
+            r"(?P<bytes_received>\d+) (?P<bytes_sent>\d+) (?P<duration>\d+|-) (?P<upstream_service_time>\d+|-) ",
+            r#""(?P<x_forwarded_for>[^"]*)" "(?P<user_agent>[^"]*)" "(?P<request_id>[^"]*)" "(?P<authority>[^"]*)" "(?P<upstream_host>[^"]*)""#,
+            r"(?: (?P<upstream_cluster>\S+) (?P<upstream_local_address>\S+) (?P<downstream_local_address>\S+) (?P<downstream_remote_address>\S+) (?P<requested_server_name>\S+) (?P<route_name>\S+))?",
+        ))?;
+
+        Ok(Self {
+            line_regex,
+            timestamp_parser: TimestampParser::default(),
+        })
+    }
+}
+
+impl Default for EnvoyParser {
+    fn default() -> Self {
+        Self::new().expect("Failed to create default EnvoyParser")
+    }
+}
+
+impl Parser for EnvoyParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let mut entry = LogEntry::new(line.to_string());
+
+        let captures = match self.line_regex.captures(line) {
+            Some(captures) => captures,
+            None => return Ok(Some(entry)),
+        };
+
+        if let Some(timestamp) = self.timestamp_parser.parse(&captures["start_time"]) {
+            entry = entry.with_timestamp(timestamp);
+        }
+
+        for name in [
+            "response_flags",
+            "response_code_details",
+            "connection_termination_details",
+            "upstream_transport_failure_reason",
+            "x_forwarded_for",
+            "user_agent",
+            "request_id",
+            "authority",
+            "upstream_host",
+            "upstream_cluster",
+            "requested_server_name",
+            "route_name",
+        ] {
+            if let Some(value) = captures.name(name) {
+                if !matches!(value.as_str(), "-" | "") {
+                    entry = entry.with_field(name, value.as_str());
+                }
+            }
+        }
+
+        // Addresses are `ip:port`, optionally with a scheme (`tcp://10.0.2.1:80`)
+        for name in [
+            "upstream_host",
+            "upstream_local_address",
+            "downstream_local_address",
+            "downstream_remote_address",
+        ] {
+            if let Some(address) = captures.name(name) {
+                let address = address.as_str();
+                let address = address.split_once("://").map_or(address, |(_, rest)| rest);
+                let prefix = name.trim_end_matches("_address").trim_end_matches("_host");
+                entry = with_addr_port(entry, prefix, address);
+            }
+        }
+
+        for name in ["bytes_received", "bytes_sent"] {
+            if let Ok(value) = captures[name].parse::<i64>() {
+                entry = entry.with_field(name, value);
+            }
+        }
+        for name in ["duration", "upstream_service_time"] {
+            if let Some(duration) = millis(&captures, name) {
+                entry = entry.with_field(name, duration);
+            }
+        }
+
+        let mut level = LogLevel::Info;
+        if let Ok(status) = captures["response_code"].parse::<i64>() {
+            entry = entry.with_field("response_code", status);
+            // 0 means the response was never sent (downstream disconnect, ...)
+            level = if status == 0 {
+                LogLevel::Warn
+            } else {
+                level_from_status(status)
+            };
+        }
+        if &captures["response_flags"] != "-" {
+            level = level.max(LogLevel::Warn);
+        }
+        entry = entry.with_level(level);
+
+        Ok(Some(with_request(entry, &captures["request"])))
+    }
+
+    fn name(&self) -> &'static str {
+        "envoy"
+    }
+}
+
+// Millisecond timers; negative values mean the phase never happened
+fn millis(captures: &Captures, name: &str) -> Option<Duration> {
+    let value: i64 = captures.name(name)?.as_str().parse().ok()?;
+    u64::try_from(value).ok().map(Duration::from_millis)
+}
+
+// Store `ip:port` as `<prefix>_ip` and `<prefix>_port`; anything else is left out
+fn with_addr_port(entry: LogEntry, prefix: &str, address: &str) -> LogEntry {
+    let (ip, port) = match address.rsplit_once(':') {
+        Some((ip, port)) => (ip.trim_start_matches('[').trim_end_matches(']'), port),
+        None => return entry,
+    };
+    match (ip.parse::<IpAddr>(), port.parse::<i64>()) {
+        (Ok(ip), Ok(port)) => entry
+            .with_field(format!("{}_ip", prefix), ip)
+            .with_field(format!("{}_port", prefix), port),
+        _ => entry,
+    }
+}
+
+// `METHOD url PROTOCOL` becomes the message and the method/url/protocol fields
+fn with_request(mut entry: LogEntry, request: &str) -> LogEntry {
+    let mut parts = request.split(' ');
+    if let (Some(method), Some(url)) = (parts.next(), parts.next()) {
+        entry = entry.with_field("method", method).with_field("url", url);
+        if let Some(protocol) = parts.next() {
+            entry = entry.with_field("protocol", protocol);
+        }
+    }
+    entry.with_message(request.to_string())
+}
@@ -0,0 +1,442 @@
+use crate::core::{FieldValue, LogEntry, LogLevel, Result, SourceTimezone, TimestampParser};
+use crate::parsers::Parser;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use std::net::IpAddr;
+
+// Extension keys with a typed value; everything else is kept as a string
+const CEF_ADDRESS_KEYS: &[&str] = &[
+    "src",
+    "dst",
+    "dvc",
+    "sourceTranslatedAddress",
+    "destinationTranslatedAddress",
+    "deviceTranslatedAddress",
+];
+const CEF_NUMBER_KEYS: &[&str] = &[
+    "spt",
+    "dpt",
+    "in",
+    "out",
+    "cnt",
+    "cn1",
+    "cn2",
+    "cn3",
+    "dvcpid",
+    "spid",
+    "dpid",
+    "fsize",
+    "oldFileSize",
+    "sourceTranslatedPort",
+    "destinationTranslatedPort",
+];
+const CEF_TIME_KEYS: &[&str] = &[
+    "rt",
+    "start",
+    "end",
+    "deviceCustomDate1",
+    "deviceCustomDate2",
+];
+
+const LEEF_ADDRESS_KEYS: &[&str] = &[
+    "src",
+    "dst",
+    "srcPreNAT",
+    "dstPreNAT",
+    "srcPostNAT",
+    "dstPostNAT",
+    "identSrc",
+];
+const LEEF_NUMBER_KEYS: &[&str] = &[
+    "srcPort",
+    "dstPort",
+    "srcPreNATPort",
+    "dstPreNATPort",
+    "srcPostNATPort",
+    "dstPostNATPort",
+    "srcBytes",
+    "dstBytes",
+    "totalBytes",
+    "srcPackets",
+    "dstPackets",
+    "totalPackets",
+    "sev",
+];
+
+/// Parser for ArcSight Common Event Format events, optionally behind a syslog header:
+/// `CEF:0|Vendor|Product|1.0|100|Port scan detected|7|src=10.0.0.1 spt=4444 act=blocked`.
+///
+/// The header becomes the `device_vendor`, `device_product`, `device_version`,
+/// `signature_id` and `severity` fields with the event name as message; the severity
+/// (`0`–`10` or `Low` to `Very-High`) sets the level. Extension keys keep their CEF names,
+/// with addresses, ports and counters typed and `csN`/`cnN` values also stored under their
+/// `csNLabel`. `rt` (or the syslog header) is the timestamp.
+pub struct CefParser {
+    syslog_regex: Regex,
+    extension_key_regex: Regex,
+    timestamp_parser: TimestampParser,
+}
+
+impl CefParser {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            syslog_regex: syslog_header_regex()?,
+            extension_key_regex: Regex::new(r"(?:^|\s)([A-Za-z0-9_.\[\]-]+)=")?,
+            timestamp_parser: TimestampParser::default(),
+        })
+    }
+
+    pub fn with_timezone(mut self, timezone: SourceTimezone) -> Self {
+        self.timestamp_parser = TimestampParser::new(timezone);
+        self
+    }
+
+    // Split on unescaped `=` preceded by a key; values may contain spaces
+    fn parse_extension(&self, extension: &str) -> Vec<(String, String)> {
+        let keys: Vec<_> = self
+            .extension_key_regex
+            .captures_iter(extension)
+            .filter_map(|captures| {
+                let whole = captures.get(0)?;
+                let key = captures.get(1)?;
+                Some((whole.start(), key.as_str(), whole.end()))
+            })
+            .collect();
+
+        keys.iter()
+            .enumerate()
+            .map(|(i, &(_, key, value_start))| {
+                let value_end = keys.get(i + 1).map_or(extension.len(), |next| next.0);
+                let value = unescape(extension[value_start..value_end].trim_end());
+                (key.to_string(), value)
+            })
+            .collect()
+    }
+}
+
+impl Default for CefParser {
+    fn default() -> Self {
+        Self::new().expect("Failed to create default CefParser")
+    }
+}
+
+impl Parser for CefParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let mut entry = LogEntry::new(line.to_string());
+
+        let (prefix, event) = match line.find("CEF:") {
+            Some(start) => (&line[..start], &line[start + "CEF:".len()..]),
+            None => return Ok(Some(entry)),
+        };
+        let (header, extension) = match split_header(event, 7) {
+            Some(split) => split,
+            None => return Ok(Some(entry)),
+        };
+
+        entry = with_syslog_header(entry, &self.syslog_regex, &self.timestamp_parser, prefix);
+
+        for (name, value) in [
+            "cef_version",
+            "device_vendor",
+            "device_product",
+            "device_version",
+            "signature_id",
+        ]
+        .into_iter()
+        .zip(&header)
+        {
+            entry = entry.with_field(name, value.as_str());
+        }
+
+        let severity = header[6].as_str();
+        entry = match severity.parse::<i64>() {
+            Ok(number) => entry.with_field("severity", number),
+            Err(_) => entry.with_field("severity", severity),
+        };
+        if let Some(level) = level_from_severity(severity) {
+            entry = entry.with_level(level);
+        }
+        entry = entry.with_message(header[5].clone());
+
+        let extension = self.parse_extension(extension);
+        for (key, value) in &extension {
+            if value.is_empty() || key.ends_with("Label") {
+                continue;
+            }
+
+            if CEF_TIME_KEYS.contains(&key.as_str()) {
+                if let Some(timestamp) = parse_event_time(&self.timestamp_parser, value) {
+                    if key == "rt" {
+                        entry = entry.with_timestamp(timestamp);
+                    }
+                    entry = entry.with_field(key.as_str(), timestamp);
+                    continue;
+                }
+            }
+            let typed = typed_value(key, value, CEF_ADDRESS_KEYS, CEF_NUMBER_KEYS);
+
+            // cs1=Block cs1Label=Policy is also stored as Policy=Block
+            let label_key = format!("{}Label", key);
+            if let Some((_, label)) = extension.iter().find(|(k, _)| *k == label_key) {
+                if !label.is_empty() {
+                    entry = entry.with_field(label.as_str(), typed.clone());
+                }
+            }
+            entry = entry.with_field(key.as_str(), typed);
+        }
+
+        Ok(Some(entry))
+    }
+
+    fn name(&self) -> &'static str {
+        "cef"
+    }
+}
+
+/// Parser for IBM QRadar Log Event Extended Format events, optionally behind a syslog
+/// header: `LEEF:1.0|Vendor|Product|1.0|EventID|src=10.0.0.1<TAB>dst=10.0.0.2<TAB>sev=5`.
+///
+/// LEEF 2.0 names the attribute delimiter in an extra header field (`^`, `x5E`, `0x5E`);
+/// LEEF 1.0 uses tabs. The header becomes `device_vendor`, `device_product`,
+/// `device_version` and `event_id` (also the message), `sev` sets the level and `devTime`
+/// the timestamp.
+pub struct LeefParser {
+    syslog_regex: Regex,
+    timestamp_parser: TimestampParser,
+}
+
+impl LeefParser {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            syslog_regex: syslog_header_regex()?,
+            timestamp_parser: TimestampParser::default(),
+        })
+    }
+
+    pub fn with_timezone(mut self, timezone: SourceTimezone) -> Self {
+        self.timestamp_parser = TimestampParser::new(timezone);
+        self
+    }
+}
+
+impl Default for LeefParser {
+    fn default() -> Self {
+        Self::new().expect("Failed to create default LeefParser")
+    }
+}
+
+impl Parser for LeefParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let mut entry = LogEntry::new(line.to_string());
+
+        let (prefix, event) = match line.find("LEEF:") {
+            Some(start) => (&line[..start], &line[start + "LEEF:".len()..]),
+            None => return Ok(Some(entry)),
+        };
+        let version = event.split('|').next().unwrap_or_default();
+        let header_fields = if version.starts_with('2') { 6 } else { 5 };
+        let (header, attributes) = match split_header(event, header_fields) {
+            Some(split) => split,
+            None => return Ok(Some(entry)),
+        };
+        let delimiter = match header.get(5) {
+            Some(delimiter) => parse_delimiter(delimiter),
+            None => '\t',
+        };
+
+        entry = with_syslog_header(entry, &self.syslog_regex, &self.timestamp_parser, prefix);
+
+        for (name, value) in [
+            "leef_version",
+            "device_vendor",
+            "device_product",
+            "device_version",
+            "event_id",
+        ]
+        .into_iter()
+        .zip(&header)
+        {
+            entry = entry.with_field(name, value.as_str());
+        }
+        entry = entry.with_message(header[4].clone());
+
+        for attribute in attributes.split(delimiter) {
+            let (key, value) = match attribute.split_once('=') {
+                Some((key, value)) if !key.trim().is_empty() && !value.is_empty() => {
+                    (key.trim(), value)
+                }
+                _ => continue,
+            };
+
+            match key {
+                "devTime" => {
+                    if let Some(timestamp) = parse_event_time(&self.timestamp_parser, value) {
+                        entry = entry.with_timestamp(timestamp);
+                        continue;
+                    }
+                }
+                "sev" => {
+                    if let Some(level) = level_from_severity(value) {
+                        entry = entry.with_level(level);
+                    }
+                }
+                _ => {}
+            }
+            entry = entry.with_field(
+                key,
+                typed_value(key, value, LEEF_ADDRESS_KEYS, LEEF_NUMBER_KEYS),
+            );
+        }
+
+        Ok(Some(entry))
+    }
+
+    fn name(&self) -> &'static str {
+        "leef"
+    }
+}
+
+// Timestamp and host of a syslog header in front of the event
+fn syslog_header_regex() -> Result<Regex> {
+    Ok(Regex::new(
+        r"^(?:<\d+>)?(?:1 )?(?P<time>\d{4}-\d{2}-\d{2}T\S+|[A-Z][a-z]{2} +\d{1,2} \d{2}:\d{2}:\d{2})(?: (?P<host>[^\s|]+))?",
+    )?)
+}
+
+fn with_syslog_header(
+    mut entry: LogEntry,
+    syslog_regex: &Regex,
+    timestamp_parser: &TimestampParser,
+    prefix: &str,
+) -> LogEntry {
+    if let Some(captures) = syslog_regex.captures(prefix.trim()) {
+        if let Some(timestamp) = timestamp_parser.parse(&captures["time"]) {
+            entry = entry.with_timestamp(timestamp);
+        }
+        if let Some(host) = captures.name("host") {
+            entry = entry.with_field("host", host.as_str());
+        }
+    }
+    entry
+}
+
+// The first `count` `|`-separated header fields (with `\|` and `\\` escapes) and the rest
+fn split_header(event: &str, count: usize) -> Option<(Vec<String>, &str)> {
+    let mut fields = Vec::with_capacity(count);
+    let mut field = String::new();
+    let mut chars = event.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, escaped @ ('|' | '\\'))) => field.push(escaped),
+                Some((_, other)) => {
+                    field.push('\\');
+                    field.push(other);
+                }
+                None => field.push('\\'),
+            },
+            '|' => {
+                fields.push(std::mem::take(&mut field));
+                if fields.len() == count {
+                    return Some((fields, &event[i + 1..]));
+                }
+            }
+            c => field.push(c),
+        }
+    }
+
+    None
+}
+
+// Extension values escape `\`, `=` and line breaks; other backslashes (Windows paths
+// from careless producers) are kept
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(escaped @ ('\\' | '=' | '|')) => unescaped.push(escaped),
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+fn typed_value(key: &str, value: &str, addresses: &[&str], numbers: &[&str]) -> FieldValue {
+    if addresses.contains(&key) {
+        if let Ok(ip) = value.parse::<IpAddr>() {
+            return FieldValue::Ip(ip);
+        }
+    } else if numbers.contains(&key) {
+        if let Ok(number) = value.parse::<i64>() {
+            return FieldValue::Int(number);
+        }
+    }
+    FieldValue::from(value)
+}
+
+// CEF: 0-3 Low, 4-6 Medium, 7-8 High, 9-10 Very-High; LEEF `sev` uses the same 1-10 scale
+fn level_from_severity(severity: &str) -> Option<LogLevel> {
+    let level = match severity.trim().to_ascii_lowercase().as_str() {
+        "low" => LogLevel::Info,
+        "medium" => LogLevel::Warn,
+        "high" => LogLevel::Error,
+        "very-high" => LogLevel::Critical,
+        number => match number.parse::<u8>().ok()? {
+            0..=3 => LogLevel::Info,
+            4..=6 => LogLevel::Warn,
+            7..=8 => LogLevel::Error,
+            9..=10 => LogLevel::Critical,
+            _ => return None,
+        },
+    };
+    Some(level)
+}
+
+// Epoch milliseconds or `MMM dd yyyy HH:mm:ss[.SSS][ zzz]`, the date format both specs use
+fn parse_event_time(timestamp_parser: &TimestampParser, value: &str) -> Option<DateTime<Utc>> {
+    const FORMAT: &str = "%b %d %Y %H:%M:%S%.f";
+
+    if let Some(timestamp) = timestamp_parser.parse(value) {
+        return Some(timestamp);
+    }
+    if let Some((time, zone)) = value.rsplit_once(' ') {
+        if let Ok(timezone) = zone.parse::<SourceTimezone>() {
+            return TimestampParser::new(timezone).parse_with_format(time, FORMAT);
+        }
+    }
+    timestamp_parser.parse_with_format(value, FORMAT)
+}
+
+// LEEF 2.0 delimiters are a character or its hex code (`^`, `x5E`, `0x5E`)
+fn parse_delimiter(delimiter: &str) -> char {
+    let hex = delimiter
+        .strip_prefix("0x")
+        .or_else(|| delimiter.strip_prefix('x'))
+        .filter(|hex| !hex.is_empty());
+    match hex.and_then(|hex| u32::from_str_radix(hex, 16).ok()) {
+        Some(code) => char::from_u32(code).unwrap_or('\t'),
+        None => delimiter.chars().next().unwrap_or('\t'),
+    }
+}
@@ -0,0 +1,227 @@
+use crate::core::{FieldValue, LogEntry, LogLevel, Result, SourceTimezone, TimestampParser};
+use crate::parsers::Parser;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+
+const FACILITIES: [&str; 24] = [
+    "kern",
+    "user",
+    "mail",
+    "daemon",
+    "auth",
+    "syslog",
+    "lpr",
+    "news",
+    "uucp",
+    "cron",
+    "authpriv",
+    "ftp",
+    "ntp",
+    "security",
+    "console",
+    "solaris-cron",
+    "local0",
+    "local1",
+    "local2",
+    "local3",
+    "local4",
+    "local5",
+    "local6",
+    "local7",
+];
+
+const SEVERITIES: [&str; 8] = [
+    "emerg", "alert", "crit", "err", "warning", "notice", "info", "debug",
+];
+
+/// Parser for syslog messages in BSD (RFC 3164) and IETF (RFC 5424) formats.
+///
+/// The PRI part is optional for RFC 3164 so that files written by syslog daemons
+/// (`Oct 11 22:14:15 host app[123]: msg`) are understood as well.
+pub struct SyslogParser {
+    rfc5424_regex: Regex,
+    rfc3164_regex: Regex,
+    timestamp_parser: TimestampParser,
+}
+
+impl SyslogParser {
+    pub fn new() -> Result<Self> {
+        let rfc5424_regex = Regex::new(
+            r"^<(?P<pri>\d{1,3})>(?P<version>\d{1,2}) (?P<timestamp>\S+) (?P<hostname>\S+) (?P<app_name>\S+) (?P<procid>\S+) (?P<msgid>\S+) ?(?P<rest>.*)$",
+        )?;
+        let rfc3164_regex = Regex::new(
+            r"^(?:<(?P<pri>\d{1,3})>)?(?P<timestamp>[A-Z][a-z]{2} +\d{1,2} \d{2}:\d{2}:\d{2}) (?P<hostname>\S+) (?:(?P<tag>[^\s:\[]+)(?:\[(?P<pid>[^\]]*)\])?: ?)?(?P<msg>.*)$",
+        )?;
+
+        Ok(Self {
+            rfc5424_regex,
+            rfc3164_regex,
+            timestamp_parser: TimestampParser::default(),
+        })
+    }
+
+    /// Timezone of RFC 3164 timestamps, which carry no zone of their own.
+    pub fn with_timezone(mut self, timezone: SourceTimezone) -> Self {
+        self.timestamp_parser = TimestampParser::new(timezone);
+        self
+    }
+
+    fn parse_rfc5424(&self, line: &str) -> Option<LogEntry> {
+        let captures = self.rfc5424_regex.captures(line)?;
+        let mut entry = LogEntry::new(line.to_string());
+
+        entry = apply_priority(entry, &captures["pri"]);
+
+        if let Ok(timestamp) = DateTime::parse_from_rfc3339(&captures["timestamp"]) {
+            entry = entry.with_timestamp(timestamp.with_timezone(&Utc));
+        }
+
+        for name in ["hostname", "app_name", "procid", "msgid"] {
+            let value = &captures[name];
+            // "-" is the RFC 5424 NILVALUE
+            if value != "-" {
+                entry = entry.with_field(name, FieldValue::infer(value));
+            }
+        }
+
+        let rest = &captures["rest"];
+        let message = match rest.strip_prefix('-') {
+            Some(message) => message,
+            None => {
+                let (elements, message) = parse_structured_data(rest);
+                for (key, value) in elements {
+                    entry = entry.with_field(key, FieldValue::infer(&value));
+                }
+                message
+            }
+        };
+        // The message may be prefixed with a UTF-8 BOM
+        let message = message.trim_start().trim_start_matches('\u{feff}');
+
+        Some(entry.with_message(message.to_string()))
+    }
+
+    fn parse_rfc3164(&self, line: &str) -> Option<LogEntry> {
+        let captures = self.rfc3164_regex.captures(line)?;
+        let mut entry = LogEntry::new(line.to_string());
+
+        if let Some(pri) = captures.name("pri") {
+            entry = apply_priority(entry, pri.as_str());
+        }
+
+        if let Some(timestamp) = self.timestamp_parser.parse_syslog(&captures["timestamp"]) {
+            entry = entry.with_timestamp(timestamp);
+        }
+
+        entry = entry.with_field("hostname", FieldValue::infer(&captures["hostname"]));
+        if let Some(tag) = captures.name("tag") {
+            entry = entry.with_field("app_name", tag.as_str());
+        }
+        if let Some(pid) = captures.name("pid") {
+            entry = entry.with_field("procid", FieldValue::infer(pid.as_str()));
+        }
+
+        Some(entry.with_message(captures["msg"].to_string()))
+    }
+}
+
+impl Default for SyslogParser {
+    fn default() -> Self {
+        Self::new().expect("Failed to create default SyslogParser")
+    }
+}
+
+impl Parser for SyslogParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let entry = self
+            .parse_rfc5424(line)
+            .or_else(|| self.parse_rfc3164(line))
+            .unwrap_or_else(|| LogEntry::new(line.to_string()));
+
+        Ok(Some(entry))
+    }
+
+    fn name(&self) -> &'static str {
+        "syslog"
+    }
+}
+
+// Decode PRI (facility * 8 + severity) into fields and a log level
+fn apply_priority(entry: LogEntry, pri: &str) -> LogEntry {
+    let pri: u8 = match pri.parse() {
+        Ok(pri) if pri < 192 => pri,
+        _ => return entry,
+    };
+    let facility = (pri / 8) as usize;
+    let severity = pri % 8;
+
+    let entry = entry
+        .with_field("facility", FACILITIES[facility])
+        .with_field("severity", SEVERITIES[severity as usize]);
+
+    match LogLevel::from_syslog_severity(severity) {
+        Some(level) => entry.with_level(level),
+        None => entry,
+    }
+}
+
+// Split RFC 5424 STRUCTURED-DATA (`[id key="value" ...][id2 ...]`) from the message.
+// Parameters are returned as `<sd-id>.<param-name>` fields.
+fn parse_structured_data(input: &str) -> (Vec<(String, String)>, &str) {
+    let mut params = Vec::new();
+    let mut rest = input;
+
+    while let Some(element) = rest.strip_prefix('[') {
+        match parse_sd_element(element, &mut params) {
+            Some(consumed) => rest = &element[consumed..],
+            // Malformed structured data: treat the remainder as the message
+            None => break,
+        }
+    }
+
+    (params, rest)
+}
+
+// Parse one SD-ELEMENT following its `[`; returns the bytes consumed including the closing `]`
+fn parse_sd_element(element: &str, params: &mut Vec<(String, String)>) -> Option<usize> {
+    let id_end = element.find([' ', ']'])?;
+    let id = &element[..id_end];
+    let mut pos = id_end;
+
+    loop {
+        pos += element[pos..].len() - element[pos..].trim_start_matches(' ').len();
+        if element[pos..].starts_with(']') {
+            return Some(pos + 1);
+        }
+
+        let name_len = element[pos..].find("=\"")?;
+        let name = &element[pos..pos + name_len];
+        pos += name_len + 2;
+
+        // Only `"`, `\` and `]` are escapable; other backslashes are kept as-is
+        let mut value = String::new();
+        let mut chars = element[pos..].char_indices();
+        loop {
+            match chars.next()? {
+                (_, '\\') => match chars.next()? {
+                    (_, escaped @ ('"' | '\\' | ']')) => value.push(escaped),
+                    (_, other) => {
+                        value.push('\\');
+                        value.push(other);
+                    }
+                },
+                (i, '"') => {
+                    pos += i + 1;
+                    break;
+                }
+                (_, c) => value.push(c),
+            }
+        }
+
+        params.push((format!("{}.{}", id, name), value));
+    }
+}
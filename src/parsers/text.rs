@@ -1,23 +1,75 @@
-use crate::core::{LogEntry, LogLevel, Result};
+use crate::core::{LogEntry, LogLevel, Result, SourceTimezone, TimestampParser};
 use crate::parsers::Parser;
-use chrono::{DateTime, Utc};
 use regex::Regex;
 
+// Timestamp shapes searched for anywhere in a line; `TimestampParser` does the actual parsing
+const TIMESTAMP_PATTERNS: &[&str] = &[
+    // ISO 8601 with optional fraction and offset
+    r"\d{4}[-/]\d{2}[-/]\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z| ?[+-]\d{2}(?::?\d{2})?)?",
+    // Apache/Nginx access log
+    r"\d{2}/[A-Z][a-z]{2}/\d{4}:\d{2}:\d{2}:\d{2} [+-]\d{4}",
+    // RFC 2822
+    r"(?:[A-Z][a-z]{2}, )?\d{1,2} [A-Z][a-z]{2} \d{4} \d{2}:\d{2}:\d{2} (?:[+-]\d{4}|GMT|UTC)",
+    // ctime
+    r"[A-Z][a-z]{2} [A-Z][a-z]{2} +\d{1,2} \d{2}:\d{2}:\d{2}(?:\.\d+)? \d{4}",
+    // syslog
+    r"[A-Z][a-z]{2} +\d{1,2} \d{2}:\d{2}:\d{2}(?:\.\d+)?",
+    // Epoch seconds, milliseconds, microseconds or nanoseconds at the start of the line
+    r"^(?:\d{19}|\d{16}|\d{13}|\d{10})(?:\.\d+)?\b",
+    // Japanese
+    r"\d{4}年\d{1,2}月\d{1,2}日 ?(?:\d{1,2}時\d{1,2}分(?:\d{1,2}秒)?|\d{1,2}:\d{2}(?::\d{2})?)?",
+];
+
+// What may separate the level from the start of the line or the timestamp: `] ERROR`, `: [E]`;
+// the same characters as `is_level_separator`
+const LEVEL_PREFIX: &str = r"^[\s\])|,:-]*";
+
+// Keys that introduce a level value: `level=`, `severity: `
+const LEVEL_KEY: &str = r"(?:level|lvl|loglevel|severity)\s*[=:]\s*";
+
+const LEVEL_NAMES: &str =
+    "TRACE|DEBUG|INFO|NOTICE|WARN|WARNING|ERROR|ERR|CRIT|CRITICAL|FATAL|ALERT|EMERG|EMERGENCY|SEVERE";
+
 pub struct TextParser {
     timestamp_regex: Regex,
-    level_regex: Regex,
+    // Tried in order; the first one that matches decides the level
+    level_regexes: Vec<Regex>,
+    // Single letters and bare words are too common in messages to be searched for, so they
+    // only count at the start of the line or right after the timestamp
+    leading_level_regexes: Vec<Regex>,
+    timestamp_parser: TimestampParser,
 }
 
 impl TextParser {
     pub fn new() -> Result<Self> {
-        let timestamp_regex = Regex::new(r"(\d{4}-\d{2}-\d{2}[T\s]\d{2}:\d{2}:\d{2})")?;
-        let level_regex = Regex::new(r"\[(ERROR|WARN|INFO|DEBUG)\]")?;
+        let timestamp_regex = Regex::new(&format!("({})", TIMESTAMP_PATTERNS.join("|")))?;
+        let level_regexes = vec![
+            // [ERROR], [warn]
+            Regex::new(&format!(r"(?i)\[\s*({})\s*\]", LEVEL_NAMES))?,
+            // level=error, severity: "warn"; numbers and single letters only at the head
+            Regex::new(&format!(r#"(?i)\b{}"?([a-z]{{2,}})\b"?"#, LEVEL_KEY))?,
+        ];
+        let leading_level_regexes = vec![
+            // [E], [w]
+            Regex::new(&format!(r"(?i){}\[\s*([TDINWECF])\s*\]", LEVEL_PREFIX))?,
+            // ERROR: or a bare upper-case level word
+            Regex::new(&format!(r"{}({})\b:?", LEVEL_PREFIX, LEVEL_NAMES))?,
+            // level=3, lvl=E
+            Regex::new(&format!(r#"(?i){}{}"?(\w+)\b"?"#, LEVEL_PREFIX, LEVEL_KEY))?,
+        ];
 
         Ok(Self {
             timestamp_regex,
-            level_regex,
+            level_regexes,
+            leading_level_regexes,
+            timestamp_parser: TimestampParser::default(),
         })
     }
+
+    pub fn with_timezone(mut self, timezone: SourceTimezone) -> Self {
+        self.timestamp_parser = TimestampParser::new(timezone);
+        self
+    }
 }
 
 impl Default for TextParser {
@@ -34,32 +86,53 @@ impl Parser for TextParser {
 
         let mut entry = LogEntry::new(line.to_string());
 
-        // Extract timestamp
+        // Extract timestamp; a level may start where it ends
+        let mut heads = vec![0];
         if let Some(captures) = self.timestamp_regex.captures(line) {
             if let Some(timestamp_str) = captures.get(1) {
-                let timestamp_str = timestamp_str.as_str();
-                // Try different timestamp formats
-                let timestamp = try_parse_timestamp(timestamp_str);
+                let timestamp = self.timestamp_parser.parse(timestamp_str.as_str());
                 if let Some(timestamp) = timestamp {
                     entry = entry.with_timestamp(timestamp);
+                    heads.push(timestamp_str.end());
                 }
             }
         }
+        let at_head = |start: usize| {
+            heads
+                .iter()
+                .any(|&head| head <= start && line[head..start].chars().all(is_level_separator))
+        };
+
+        // Extract log level, remembering where it ends if it heads the message
+        let level_match = self
+            .level_regexes
+            .iter()
+            .find_map(|regex| {
+                let captures = regex.captures(line)?;
+                let level = captures[1].parse::<LogLevel>().ok()?;
+                let found = captures.get(0)?;
+                Some((level, at_head(found.start()).then_some(found.end())))
+            })
+            .or_else(|| {
+                heads.iter().find_map(|&head| {
+                    self.leading_level_regexes.iter().find_map(|regex| {
+                        let captures = regex.captures(&line[head..])?;
+                        let level = captures[1].parse::<LogLevel>().ok()?;
+                        Some((level, Some(head + captures.get(0)?.end())))
+                    })
+                })
+            });
 
-        // Extract log level
-        if let Some(captures) = self.level_regex.captures(line) {
-            if let Some(level_str) = captures.get(1) {
-                if let Ok(level) = level_str.as_str().parse::<LogLevel>() {
-                    entry = entry.with_level(level);
+        // Extract message (everything after a leading level, otherwise the whole line)
+        let message = match level_match {
+            Some((level, end)) => {
+                entry = entry.with_level(level);
+                match end {
+                    Some(end) => line[end..].trim().to_string(),
+                    None => line.to_string(),
                 }
             }
-        }
-
-        // Extract message (everything after the level, or the whole line if no level found)
-        let message = if let Some(level_match) = self.level_regex.find(line) {
-            line[level_match.end()..].trim().to_string()
-        } else {
-            line.to_string()
+            None => line.to_string(),
         };
         entry = entry.with_message(message);
 
@@ -71,31 +144,6 @@ impl Parser for TextParser {
     }
 }
 
-// Helper function to try parsing different timestamp formats
-fn try_parse_timestamp(timestamp_str: &str) -> Option<DateTime<Utc>> {
-    let formats = [
-        "%Y-%m-%d %H:%M:%S",      // 2024-01-01 12:00:00
-        "%Y-%m-%dT%H:%M:%S",      // 2024-01-01T12:00:00
-        "%Y-%m-%d %H:%M:%SZ",     // 2024-01-01 12:00:00Z
-        "%Y-%m-%dT%H:%M:%SZ",     // 2024-01-01T12:00:00Z
-        "%Y-%m-%d %H:%M:%S%.3f",  // 2024-01-01 12:00:00.123
-        "%Y-%m-%dT%H:%M:%S%.3f",  // 2024-01-01T12:00:00.123
-    ];
-
-    // Try parsing with explicit timezone first
-    for format in &formats {
-        if let Ok(dt) = DateTime::parse_from_str(timestamp_str, format) {
-            return Some(dt.with_timezone(&Utc));
-        }
-    }
-
-    // Try parsing as naive datetime and assume UTC
-    use chrono::NaiveDateTime;
-    for format in &formats {
-        if let Ok(naive_dt) = NaiveDateTime::parse_from_str(timestamp_str, format) {
-            return Some(naive_dt.and_utc());
-        }
-    }
-
-    None
+fn is_level_separator(c: char) -> bool {
+    c.is_whitespace() || matches!(c, ']' | ')' | '|' | ',' | ':' | '-')
 }
@@ -0,0 +1,204 @@
+use crate::core::{FieldValue, LogEntry, Result, SourceTimezone, TimestampParser};
+use crate::parsers::access::level_from_status;
+use crate::parsers::Parser;
+use std::time::Duration;
+
+// Column layout IIS writes by default, used until a `#Fields` directive is seen
+const DEFAULT_FIELDS: &[&str] = &[
+    "date",
+    "time",
+    "s-ip",
+    "cs-method",
+    "cs-uri-stem",
+    "cs-uri-query",
+    "s-port",
+    "cs-username",
+    "c-ip",
+    "cs(User-Agent)",
+    "cs(Referer)",
+    "sc-status",
+    "sc-substatus",
+    "sc-win32-status",
+    "time-taken",
+];
+
+// Columns of CloudFront standard logs, used until a `#Fields` directive is seen
+const CLOUDFRONT_FIELDS: &[&str] = &[
+    "date",
+    "time",
+    "x-edge-location",
+    "sc-bytes",
+    "c-ip",
+    "cs-method",
+    "cs(Host)",
+    "cs-uri-stem",
+    "sc-status",
+    "cs(Referer)",
+    "cs(User-Agent)",
+    "cs-uri-query",
+    "cs(Cookie)",
+    "x-edge-result-type",
+    "x-edge-request-id",
+    "x-host-header",
+    "cs-protocol",
+    "cs-bytes",
+    "time-taken",
+    "x-forwarded-for",
+    "ssl-protocol",
+    "ssl-cipher",
+    "x-edge-response-result-type",
+    "cs-protocol-version",
+    "fle-status",
+    "fle-encrypted-fields",
+    "c-port",
+    "time-to-first-byte",
+    "x-edge-detailed-result-type",
+    "sc-content-type",
+    "sc-content-len",
+    "sc-range-start",
+    "sc-range-end",
+];
+
+/// Parser for the W3C Extended Log File Format used by IIS and many CDNs.
+///
+/// `#Fields:` directives define the columns of the following lines and may change in the
+/// middle of a file. `date` and `time` (or `time` and the `#Date` directive) become the
+/// timestamp, the other columns become fields under their W3C names (`c-ip`, `sc-status`,
+/// `cs(User-Agent)`, ...). `-` marks an empty column.
+pub struct W3cParser {
+    name: &'static str,
+    columns: Vec<String>,
+    // Columns holding a duration in seconds, stored as durations so `>500ms` filters work
+    seconds_columns: &'static [&'static str],
+    // Date of the `#Date` directive, for logs without a `date` column
+    directive_date: Option<String>,
+    timestamp_parser: TimestampParser,
+}
+
+impl W3cParser {
+    pub fn new() -> Self {
+        Self::with_default_fields("w3c", DEFAULT_FIELDS, &[])
+    }
+
+    /// Amazon CloudFront standard logs: tab-separated W3C with `time-taken` in seconds.
+    pub fn cloudfront() -> Self {
+        Self::with_default_fields(
+            "cloudfront",
+            CLOUDFRONT_FIELDS,
+            &["time-taken", "time-to-first-byte"],
+        )
+    }
+
+    fn with_default_fields(
+        name: &'static str,
+        fields: &[&str],
+        seconds_columns: &'static [&'static str],
+    ) -> Self {
+        Self {
+            name,
+            columns: fields.iter().map(|field| field.to_string()).collect(),
+            seconds_columns,
+            directive_date: None,
+            timestamp_parser: TimestampParser::default(),
+        }
+    }
+
+    pub fn with_timezone(mut self, timezone: SourceTimezone) -> Self {
+        self.timestamp_parser = TimestampParser::new(timezone);
+        self
+    }
+
+    fn apply_directive(&mut self, directive: &str) {
+        let (name, value) = match directive.split_once(':') {
+            Some((name, value)) => (name.trim(), value.trim()),
+            None => return,
+        };
+
+        match name {
+            "Fields" => self.columns = value.split_whitespace().map(str::to_string).collect(),
+            "Date" => {
+                self.directive_date = value.split_whitespace().next().map(str::to_string);
+            }
+            // Version, Software, Remark, Start-Date, End-Date are informational
+            _ => {}
+        }
+    }
+}
+
+impl Default for W3cParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for W3cParser {
+    fn parse_line(&self, line: &str) -> Result<Option<LogEntry>> {
+        if line.trim().is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+
+        let mut entry = LogEntry::new(line.to_string());
+
+        // Columns are separated by spaces (IIS) or tabs (CloudFront and other CDNs)
+        let values: Vec<&str> = line.split_whitespace().collect();
+        if values.len() != self.columns.len() {
+            return Ok(Some(entry));
+        }
+
+        let mut date = self.directive_date.as_deref();
+        let mut time = None;
+        for (column, value) in self.columns.iter().zip(values) {
+            match column.as_str() {
+                "date" => date = Some(value),
+                "time" => time = Some(value),
+                _ if value == "-" => {}
+                column if self.seconds_columns.contains(&column) => {
+                    if let Some(duration) = value.parse().ok().and_then(seconds_to_duration) {
+                        entry = entry.with_field(column, duration);
+                    }
+                }
+                column => entry = entry.with_field(column, FieldValue::infer(value)),
+            }
+        }
+
+        if let (Some(date), Some(time)) = (date, time) {
+            if let Some(timestamp) = self.timestamp_parser.parse(&format!("{} {}", date, time)) {
+                entry = entry.with_timestamp(timestamp);
+            }
+        }
+
+        if let Some(status) = entry.field("sc-status").and_then(FieldValue::as_i64) {
+            entry = entry.with_level(level_from_status(status));
+        }
+
+        let method = entry.field("cs-method").map(|value| value.to_string());
+        let stem = entry.field("cs-uri-stem").map(|value| value.to_string());
+        if let (Some(method), Some(stem)) = (method, stem) {
+            let message = match entry.field("cs-uri-query") {
+                Some(query) => format!("{} {}?{}", method, stem, query),
+                None => format!("{} {}", method, stem),
+            };
+            entry = entry.with_message(message);
+        }
+
+        Ok(Some(entry))
+    }
+
+    fn feed_line(&mut self, line: &str, number: usize) -> Result<Option<(usize, LogEntry)>> {
+        match line.strip_prefix('#') {
+            Some(directive) => {
+                self.apply_directive(directive);
+                Ok(None)
+            }
+            None => Ok(self.parse_line(line)?.map(|entry| (number, entry))),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+pub(crate) fn seconds_to_duration(seconds: f64) -> Option<Duration> {
+    Duration::try_from_secs_f64(seconds).ok()
+}
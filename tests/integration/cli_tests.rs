@@ -44,4 +44,930 @@ fn test_basic_log_processing() {
         .assert()
         .success();
         // Additional assertions will be added when core functionality is implemented
-}
\ No newline at end of file
+}
+#[test]
+fn test_access_log_input_format() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] \"GET /index.html HTTP/1.0\" 200 2326 \"-\" \"curl/8.0\"\n\
+         10.0.0.1 - - [10/Oct/2000:13:56:36 -0700] \"POST /api HTTP/1.1\" 502 -"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "access", "--level", "error", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"status\": 502"))
+        .stdout(predicate::str::contains("/index.html").not());
+}
+
+#[test]
+fn test_status_filter_with_csv_output() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "127.0.0.1 - - [10/Oct/2000:13:55:36 -0700] \"GET / HTTP/1.0\" 200 512\n\
+         10.0.0.1 - - [10/Oct/2000:13:56:36 -0700] \"GET /missing HTTP/1.1\" 404 0\n\
+         10.0.0.2 - - [10/Oct/2000:13:57:36 -0700] \"POST /api HTTP/1.1\" 503 0"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["-i", "access", "--status", "5xx", "--ip", "10.0.0.0/8", "--format", "csv"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("timestamp,level,message,source,bytes,method,path"))
+        .stdout(predicate::str::contains("POST /api HTTP/1.1"))
+        .stdout(predicate::str::contains("/missing").not());
+}
+
+#[test]
+fn test_status_pattern_with_many_wildcards() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "10.0.0.2 - - [10/Oct/2000:13:57:36 -0700] \"POST /api HTTP/1.1\" 503 0"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["-i", "access", "--status", "5xxxxxxxxxxxxxxxxxxxx"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("該当するログエントリが見つかりませんでした"));
+}
+
+#[test]
+fn test_json_lines_key_mapping() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "{{\"ts\":\"2024-01-01T12:00:00Z\",\"lvl\":\"error\",\"msg\":\"db timeout\",\"http\":{{\"status\":500}}}}\n\
+         {{\"time\":\"2024-01-01T12:01:00Z\",\"severity\":\"info\",\"message\":\"started\"}}\n\
+         not json at all"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "json", "--level", "error", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"message\": \"db timeout\""))
+        .stdout(predicate::str::contains("\"http.status\": 500"))
+        .stdout(predicate::str::contains("started").not());
+}
+
+#[test]
+fn test_logfmt_duration_filter() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "time=2024-01-01T12:00:00Z level=warn msg=\"slow query\" duration=1.2s\n\
+         time=2024-01-01T12:00:01Z level=info msg=\"fast query\" duration=15ms"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "logfmt", "--where", "duration>500ms"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("slow query"))
+        .stdout(predicate::str::contains("fast query").not());
+}
+
+#[test]
+fn test_custom_format_from_config_file() {
+    let mut config_file = NamedTempFile::new().unwrap();
+    writeln!(
+        config_file,
+        "[formats.myapp]\n\
+         pattern = \"%{{TIMESTAMP}} [%{{LEVEL}}] %{{WORD:module}}: %{{MSG}}\"\n\
+         levels = {{ E = \"error\", W = \"warn\" }}"
+    ).unwrap();
+
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "2024-01-01 12:00:00 [E] db: connection lost\n\
+         2024-01-01 12:00:01 [W] cache: miss ratio high"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .arg("--config")
+        .arg(config_file.path())
+        .args(["--input-format", "myapp", "--where", "module=db", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"message\": \"connection lost\""))
+        .stdout(predicate::str::contains("\"level\": \"Error\""))
+        .stdout(predicate::str::contains("cache").not());
+}
+
+#[test]
+fn test_grok_with_user_pattern_file() {
+    let mut patterns = NamedTempFile::new().unwrap();
+    writeln!(
+        patterns,
+        "REQID [A-Z]{{3}}-%{{INT}}\n\
+         REQLOG %{{REQID:[req][id]}} took %{{NUMBER:elapsed:float}}ms"
+    ).unwrap();
+
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(temp_file, "ABC-12 took 5.5ms\nXYZ-99 took 120ms").unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--grok", "^%{REQLOG}", "--where", "elapsed>100", "--format", "json"])
+        .arg("--grok-patterns")
+        .arg(patterns.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"req.id\": \"XYZ-99\""))
+        .stdout(predicate::str::contains("ABC-12").not());
+}
+
+#[test]
+fn test_auto_input_format_detection() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "<34>Oct 11 22:14:15 mymachine su: 'su root' failed for lonvick\n\
+         <38>Oct 11 22:14:16 mymachine sshd[42]: Accepted publickey"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "auto", "--level", "error"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("syslog"))
+        .stdout(predicate::str::contains("'su root' failed"))
+        .stdout(predicate::str::contains("Accepted publickey").not());
+}
+
+#[test]
+fn test_syslog_hostname_typed_in_both_flavours() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "<34>Oct 11 22:14:15 10.1.2.3 su: bsd inside\n\
+         <34>Oct 11 22:14:16 192.168.0.9 su: bsd outside\n\
+         <165>1 2024-01-01T10:00:00Z 10.9.9.9 app - - - ietf inside"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "syslog", "--where", "hostname=10.0.0.0/8"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("bsd inside"))
+        .stdout(predicate::str::contains("ietf inside"))
+        .stdout(predicate::str::contains("bsd outside").not());
+}
+
+#[test]
+fn test_auto_detection_falls_back_to_text() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "just some notes\n\
+         nothing structured here"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "auto"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("入力形式を自動判定: text (信頼度 0%)"))
+        .stdout(predicate::str::contains("nothing structured here"));
+}
+
+#[test]
+fn test_multiline_stack_trace_kept_with_error() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "2024-01-01 12:00:00 [INFO] Application started\n\
+         2024-01-01 12:00:01 [ERROR] Request failed\n\
+         java.lang.IllegalStateException: boom\n\
+         \tat com.example.Foo.bar(Foo.java:10)\n\
+         Caused by: java.io.IOException: disk full\n\
+         2024-01-01 12:00:02 [INFO] Retrying"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--multiline", "--level", "error"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("at com.example.Foo.bar"))
+        .stdout(predicate::str::contains("Caused by: java.io.IOException"))
+        .stdout(predicate::str::contains("Retrying").not());
+}
+
+#[test]
+fn test_timezone_applies_to_naive_timestamps() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "2024/01/15 09:30:00,250 [INFO] naive local time\n\
+         2024-01-15T09:30:00+02:00 [INFO] explicit offset"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--timezone", "Asia/Tokyo", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2024-01-15T00:30:00.250Z"))
+        .stdout(predicate::str::contains("2024-01-15T07:30:00Z"));
+}
+
+#[test]
+fn test_leading_epoch_timestamps_in_text_lines() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "1704110400123 [INFO] started\n\
+         1704110400 [WARN] seconds\n\
+         1704110400123456789 [ERROR] nanoseconds"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2024-01-01T12:00:00.123Z"))
+        .stdout(predicate::str::contains(r#""timestamp": "2024-01-01T12:00:00Z""#))
+        .stdout(predicate::str::contains("2024-01-01T12:00:00.123456789Z"));
+}
+
+#[test]
+fn test_level_is_minimum_and_only_level_is_exact() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "2024-01-01 12:00:00 ERROR: database unreachable\n\
+         2024-01-01 12:00:01 level=warn msg=\"slow query\"\n\
+         2024-01-01 12:00:02 [I] started\n\
+         2024-01-01 12:00:03 [FATAL] out of memory"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--level", "warn"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("database unreachable"))
+        .stdout(predicate::str::contains("slow query"))
+        .stdout(predicate::str::contains("out of memory"))
+        .stdout(predicate::str::contains("started").not());
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--only-level", "W"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("slow query"))
+        .stdout(predicate::str::contains("database unreachable").not());
+}
+
+#[test]
+fn test_level_words_inside_the_message_are_ignored() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "2024-01-01 12:00:00 values arr[i] updated\n\
+         2024-01-01 12:00:01 see [e] for details\n\
+         2024-01-01 12:00:02 [E] disk full\n\
+         2024-01-01 12:00:03 Battery level: 3 remaining"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--level", "info", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""message": "disk full""#))
+        .stdout(predicate::str::contains("arr[i]").not())
+        .stdout(predicate::str::contains("see [e]").not())
+        .stdout(predicate::str::contains("Battery").not());
+}
+
+#[test]
+fn test_cri_partial_lines_are_joined() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "2024-01-01T12:00:00.000000001Z stdout P [ERROR] connection \n\
+         2024-01-01T12:00:00.000000002Z stderr F [INFO] unrelated\n\
+         2024-01-01T12:00:00.000000003Z stdout F reset by peer"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "cri", "--inner-format", "text"])
+        .args(["--level", "error", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""message": "connection reset by peer""#))
+        .stdout(predicate::str::contains(r#""stream": "stdout""#))
+        .stdout(predicate::str::contains("unrelated").not());
+}
+
+#[test]
+fn test_docker_json_file_with_inner_json() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        r#"{{"log":"{{\"level\":\"warn\",\"msg\":\"disk almost full\"}}\n","stream":"stderr","time":"2024-01-01T12:00:00Z"}}"#
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "docker", "--inner-format", "json", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""level": "Warn""#))
+        .stdout(predicate::str::contains(r#""message": "disk almost full""#))
+        .stdout(predicate::str::contains(r#""stream": "stderr""#));
+}
+
+#[test]
+fn test_nested_container_formats_rejected_in_any_case() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(temp_file, r#"{{"log":"hello\n","stream":"stdout","time":"2024-01-01T12:00:00Z"}}"#).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "docker", "--inner-format", "Docker"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Container formats cannot be nested: Docker"));
+}
+
+#[test]
+fn test_journal_export_with_binary_field() {
+    let message = b"backend\nunreachable";
+    let mut export = Vec::new();
+    export.extend_from_slice(b"__CURSOR=s=1\n__REALTIME_TIMESTAMP=1704110400000000\nPRIORITY=3\n");
+    export.extend_from_slice(b"_SYSTEMD_UNIT=nginx.service\n_PID=812\nMESSAGE\n");
+    export.extend_from_slice(&(message.len() as u64).to_le_bytes());
+    export.extend_from_slice(message);
+    export.extend_from_slice(b"\n\n__REALTIME_TIMESTAMP=1704110401000000\nPRIORITY=6\n");
+    export.extend_from_slice(b"_SYSTEMD_UNIT=sshd.service\nMESSAGE=Accepted publickey\n\n");
+
+    let mut temp_file = NamedTempFile::new().unwrap();
+    temp_file.write_all(&export).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "journal-export", "--level", "error", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""timestamp": "2024-01-01T12:00:00Z""#))
+        .stdout(predicate::str::contains(r#""message": "backend\nunreachable""#))
+        .stdout(predicate::str::contains(r#""_SYSTEMD_UNIT": "nginx.service""#))
+        .stdout(predicate::str::contains(r#""_PID": 812"#))
+        .stdout(predicate::str::contains("Accepted publickey").not());
+}
+
+#[test]
+fn test_w3c_fields_directive_change() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "#Software: Microsoft Internet Information Services 10.0\n\
+         #Fields: date time c-ip cs-method cs-uri-stem sc-status time-taken\n\
+         2024-01-01 12:00:00 192.168.1.5 GET /index.html 200 15\n\
+         #Date: 2024-01-02 00:00:00\n\
+         #Fields: time cs-method cs-uri-stem sc-status\n\
+         09:30:00 POST /api/orders 503"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "w3c", "--where", "sc-status>=500", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""timestamp": "2024-01-02T09:30:00Z""#))
+        .stdout(predicate::str::contains(r#""message": "POST /api/orders""#))
+        .stdout(predicate::str::contains("index.html").not());
+}
+
+#[test]
+fn test_alb_status_and_latency_filters() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "https 2024-01-01T12:00:00.100000Z app/lb/abc 192.168.1.10:2817 10.0.0.1:80 0.001 0.045 0.000 200 200 0 57 \"GET https://example.com:443/fast HTTP/1.1\" \"curl/8.0\" - - - \"Root=1-abc\" \"-\" \"-\" 0 2024-01-01T12:00:00.000000Z \"forward\" \"-\" \"-\" \"-\" \"-\" \"-\" \"-\"\n\
+         https 2024-01-01T12:00:01.100000Z app/lb/abc 192.168.1.11:2817 10.0.0.1:80 0.001 1.250 0.000 504 - 0 57 \"GET https://example.com:443/slow HTTP/1.1\" \"curl/8.0\" - - - \"Root=1-def\" \"-\" \"-\" 0 2024-01-01T12:00:00.000000Z \"forward\" \"-\" \"-\" \"-\" \"-\" \"-\" \"-\""
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "alb", "--status", "5xx"])
+        .args(["--where", "target_processing_time>500ms", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""client_ip": "192.168.1.11""#))
+        .stdout(predicate::str::contains(r#""elb_status_code": 504"#))
+        .stdout(predicate::str::contains("/fast").not());
+}
+
+#[test]
+fn test_haproxy_timers_and_envoy_detection() {
+    let mut haproxy_log = NamedTempFile::new().unwrap();
+    writeln!(
+        haproxy_log,
+        "Feb  6 12:14:14 lb1 haproxy[14389]: 10.0.1.2:33317 [06/Feb/2009:12:14:14.655] http-in static/srv1 10/0/30/69/109 200 2750 - - ---- 1/1/1/1/0 0/0 {{1wt.eu}} {{}} \"GET /fast HTTP/1.1\"\n\
+         Feb  6 12:14:16 lb1 haproxy[14389]: 10.0.1.3:33318 [06/Feb/2009:12:14:15.001] http-in~ dynamic/srv2 0/0/1/1500/1501 504 194 - - sH-- 3/3/2/1/0 0/0 \"GET /slow HTTP/1.1\""
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(haproxy_log.path())
+        .args(["--input-format", "haproxy", "--where", "tr>1s"])
+        .args(["--ip", "10.0.1.3", "--status", "5xx", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""backend_name": "dynamic""#))
+        .stdout(predicate::str::contains(r#""termination_state": "sH--""#))
+        .stdout(predicate::str::contains("/fast").not());
+
+    let mut envoy_log = NamedTempFile::new().unwrap();
+    writeln!(
+        envoy_log,
+        "[2024-01-01T12:00:00.310Z] \"POST /api/v1/locations HTTP/2\" 204 - 154 0 226 100 \"10.0.35.28\" \"nsq2http\" \"cc21d9b0\" \"locations\" \"tcp://10.0.2.1:80\"\n\
+         [2024-01-01T12:00:01.120Z] \"GET /api/v1/users HTTP/1.1\" 503 UF,URX 0 91 5001 - \"10.0.35.29\" \"curl/8.0\" \"dd34e1c2\" \"users\" \"10.0.2.7:8080\""
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(envoy_log.path())
+        .args(["--input-format", "auto", "--where", "duration>5s", "--format", "json"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("envoy"))
+        .stdout(predicate::str::contains(r#""response_flags": "UF,URX""#))
+        .stdout(predicate::str::contains(r#""upstream_ip": "10.0.2.7""#))
+        .stdout(predicate::str::contains("/locations").not());
+}
+
+#[test]
+fn test_nginx_and_apache_error_logs() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "2024/01/01 12:00:00 [error] 1234#5678: *99 upstream timed out (110: Connection timed out), client: 1.2.3.4, server: example.com, request: \"GET /api HTTP/1.1\", upstream: \"http://127.0.0.1:8080/api\"\n\
+         2024/01/01 12:00:01 [notice] 1234#5678: signal process started\n\
+         [Mon Jan 01 12:00:02.123456 2024] [proxy:crit] [pid 42:tid 7] [client 5.6.7.8:5000] AH01114: HTTP: failed to make connection to backend"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "auto", "--level", "error", "--format", "json"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("自動判定: error"))
+        .stdout(predicate::str::contains(r#""message": "upstream timed out (110: Connection timed out)""#))
+        .stdout(predicate::str::contains(r#""upstream": "http://127.0.0.1:8080/api""#))
+        .stdout(predicate::str::contains(r#""connection_id": 99"#))
+        .stdout(predicate::str::contains(r#""timestamp": "2024-01-01T12:00:02.123456Z""#))
+        .stdout(predicate::str::contains(r#""error_code": "AH01114""#))
+        .stdout(predicate::str::contains("signal process").not());
+}
+
+#[test]
+fn test_postgres_detail_lines_and_mysql_slow_log() {
+    let mut postgres_log = NamedTempFile::new().unwrap();
+    writeln!(
+        postgres_log,
+        "2024-01-01 12:00:00 UTC [1234]: user=app,db=shop LOG:  connection authorized\n\
+         2024-01-01 12:00:05 UTC [1240]: user=app,db=shop ERROR:  duplicate key value violates unique constraint \"users_pkey\"\n\
+         2024-01-01 12:00:05 UTC [1240]: user=app,db=shop DETAIL:  Key (id)=(1) already exists.\n\
+         2024-01-01 12:00:05 UTC [1240]: user=app,db=shop STATEMENT:  INSERT INTO users (id)\n\
+         \tVALUES (1);"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(postgres_log.path())
+        .args(["--input-format", "postgres", "--log-line-prefix", "%t [%p]: user=%u,db=%d "])
+        .args(["--level", "error", "--where", "database=shop", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""detail": "Key (id)=(1) already exists.""#))
+        .stdout(predicate::str::contains(r#""statement": "INSERT INTO users (id)\n\tVALUES (1);""#))
+        .stdout(predicate::str::contains("connection authorized").not());
+
+    let mut slow_log = NamedTempFile::new().unwrap();
+    writeln!(
+        slow_log,
+        "# Time: 2024-01-01T12:00:00.123456Z\n\
+         # User@Host: app[app] @ web1 [10.0.0.5]  Id:    42\n\
+         # Query_time: 2.501234  Lock_time: 0.000123 Rows_sent: 1  Rows_examined: 1000000\n\
+         use shop;\n\
+         SET timestamp=1704110400;\n\
+         SELECT * FROM orders\n\
+         WHERE note LIKE '%late%';\n\
+         # Time: 2024-01-01T12:00:03.000000Z\n\
+         # User@Host: app[app] @ web2 [10.0.0.6]  Id:    43\n\
+         # Query_time: 0.300000  Lock_time: 0.000000 Rows_sent: 10  Rows_examined: 10\n\
+         SET timestamp=1704110403;\n\
+         SELECT 1;"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(slow_log.path())
+        .args(["--input-format", "mysql-slow", "--where", "Query_time>2", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""Rows_examined": 1000000"#))
+        .stdout(predicate::str::contains(r#""database": "shop""#))
+        .stdout(predicate::str::contains("WHERE note LIKE"))
+        .stdout(predicate::str::contains("SELECT 1;").not());
+}
+
+#[test]
+fn test_postgres_out_of_range_duration_is_not_a_field() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "2024-01-01 12:00:00.000 UTC [1234] LOG:  duration: {} ms\n\
+         2024-01-01 12:00:01.000 UTC [1234] LOG:  duration: 1500.5 ms",
+        "9".repeat(400)
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "postgres", "--where", "duration>1s"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1500.5 ms"))
+        .stdout(predicate::str::contains("999 ms").not());
+}
+
+#[test]
+fn test_cef_extension_escapes_and_severity() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "Jan 18 11:07:53 fw01 CEF:0|Acme\\|Corp|Firewall|1.0|100|Port scan detected|8|src=10.0.0.1 dst=192.168.1.5 spt=4444 dpt=22 act=blocked msg=rule a\\=b matched cs1Label=Policy cs1=Default Deny\n\
+         Jan 18 11:07:54 fw01 CEF:0|Acme\\|Corp|Firewall|1.0|101|Connection allowed|2|src=10.0.0.2 dst=192.168.1.5 dpt=443 act=allowed"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "cef", "--level", "error", "--ip", "10.0.0.0/24"])
+        .args(["--where", "dpt<1024", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""device_vendor": "Acme|Corp""#))
+        .stdout(predicate::str::contains(r#""msg": "rule a=b matched""#))
+        .stdout(predicate::str::contains(r#""Policy": "Default Deny""#))
+        .stdout(predicate::str::contains(r#""spt": 4444"#))
+        .stdout(predicate::str::contains("Connection allowed").not());
+}
+
+#[test]
+fn test_gelf_input_and_output() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        r#"{{"version":"1.1","host":"web1","short_message":"db timeout","timestamp":1704110400.123,"level":3,"_user_id":42,"_request":{{"path":"/api"}}}}
+{{"version":"1.1","host":"web1","short_message":"request done","timestamp":1704110401,"level":6}}"#
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "gelf", "--level", "error", "--format", "gelf"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""host":"web1""#))
+        .stdout(predicate::str::contains(r#""short_message":"db timeout""#))
+        .stdout(predicate::str::contains(r#""timestamp":1704110400.123"#))
+        .stdout(predicate::str::contains(r#""level":3"#))
+        .stdout(predicate::str::contains(r#""_user_id":42"#))
+        .stdout(predicate::str::contains(r#""_request.path":"/api""#))
+        .stdout(predicate::str::contains("request done").not());
+}
+
+#[test]
+fn test_csv_header_mapping_and_multiline_cells() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "Event_Time,Severity,user,action,detail\n\
+         2024-01-01T10:00:00Z,warn,alice,login,\"failed password\n\
+         second attempt, from \"\"vpn\"\"\"\n\
+         2024-01-01T10:05:00Z,info,bob,logout,"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "csv", "--timestamp-key", "event_time", "--level-key", "severity"])
+        .args(["--message-key", "action", "--level", "warn", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""timestamp": "2024-01-01T10:00:00Z""#))
+        .stdout(predicate::str::contains(r#""message": "login""#))
+        .stdout(predicate::str::contains(r#""user": "alice""#))
+        .stdout(predicate::str::contains(r#"failed password\nsecond attempt, from \"vpn\""#))
+        .stdout(predicate::str::contains("bob").not());
+}
+
+#[test]
+fn test_csv_stray_quote_in_unquoted_cell() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        "timestamp,level,message\n\
+         2024-01-01T10:00:00Z,info,5\" screen\n\
+         2024-01-01T10:01:00Z,error,disk full\n\
+         2024-01-01T10:02:00Z,error,\"quoted \"\"x\"\", with comma\""
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "csv", "--level", "error", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""message": "disk full""#))
+        .stdout(predicate::str::contains(r#""message": "quoted \"x\", with comma""#))
+        .stdout(predicate::str::contains("screen").not());
+}
+
+#[test]
+fn test_csv_without_header_keeps_first_row() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(temp_file, "a,b,c\n1,2,3").unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .args(["--input-format", "csv"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("a,b,c"))
+        .stdout(predicate::str::contains("1,2,3"));
+}
+
+#[test]
+fn test_auto_detection_of_csv_and_tsv() {
+    let mut csv_file = NamedTempFile::new().unwrap();
+    writeln!(
+        csv_file,
+        "timestamp,level,message,user\n\
+         2024-01-01T10:00:00Z,error,disk full,alice\n\
+         2024-01-01T10:01:00Z,info,cleaned up,bob"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(csv_file.path())
+        .args(["--input-format", "auto", "--level", "error"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("入力形式を自動判定: csv"))
+        .stdout(predicate::str::contains("disk full"))
+        .stdout(predicate::str::contains("cleaned up").not());
+
+    let mut tsv_file = NamedTempFile::new().unwrap();
+    writeln!(
+        tsv_file,
+        "timestamp\tlevel\tmessage\n\
+         2024-01-01T10:00:00Z\twarn\tslow, but alive"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(tsv_file.path())
+        .args(["--input-format", "auto"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("入力形式を自動判定: tsv"))
+        .stdout(predicate::str::contains("slow, but alive"));
+
+    // Lines with commas but without a header row are not CSV
+    let mut text_file = NamedTempFile::new().unwrap();
+    writeln!(
+        text_file,
+        "first note, with a comma\n\
+         second note, also with a comma"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(text_file.path())
+        .args(["--input-format", "auto"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("入力形式を自動判定: text"));
+}
+
+#[test]
+fn test_compressed_input_detected_by_magic_bytes() {
+    // Two concatenated gzip members, as produced by `cat a.gz b.gz`, without a .gz suffix
+    let mut temp_file = NamedTempFile::new().unwrap();
+    for chunk in ["2024-01-01 10:00:00 ERROR first failure\n", "2024-01-01 10:01:00 INFO recovered\n"] {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(chunk.as_bytes()).unwrap();
+        temp_file.write_all(&encoder.finish().unwrap()).unwrap();
+    }
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(temp_file.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("first failure"))
+        .stdout(predicate::str::contains("recovered"));
+
+    let mut zstd_file = NamedTempFile::new().unwrap();
+    let compressed = zstd::encode_all("2024-01-01 10:02:00 ERROR zstd failure\n".as_bytes(), 0).unwrap();
+    zstd_file.write_all(&compressed).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(zstd_file.path())
+        .args(["--level", "error"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("zstd failure"));
+}
+
+#[test]
+fn test_multiple_inputs_globs_and_filename_prefix() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("app/old")).unwrap();
+    std::fs::write(dir.path().join("app/web.log"), "2024-01-01 10:00:00 ERROR web failure\n").unwrap();
+    std::fs::write(dir.path().join("app/old/web.log"), "2024-01-01 09:00:00 ERROR old failure\n").unwrap();
+    std::fs::write(dir.path().join("app/notes.txt"), "2024-01-01 10:00:00 ERROR not a log\n").unwrap();
+    let mut extra = NamedTempFile::new().unwrap();
+    writeln!(extra, "2024-01-01 11:00:00 ERROR extra failure").unwrap();
+
+    // Recursive directory with include/exclude globs plus an explicit file
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(dir.path().join("app"))
+        .arg(extra.path())
+        .args(["--recursive", "--include", "*.log", "--exclude", "old"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{}:2024-01-01 10:00:00 ERROR web failure",
+            dir.path().join("app/web.log").display()
+        )))
+        .stdout(predicate::str::contains(format!("{}:", extra.path().display())))
+        .stdout(predicate::str::contains("old failure").not())
+        .stdout(predicate::str::contains("not a log").not());
+
+    // A glob pattern matched by the tool itself, with the source recorded in JSON output
+    let pattern = dir.path().join("app/**/*.log");
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(pattern.to_str().unwrap())
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            r#""source": "{}""#,
+            dir.path().join("app/old/web.log").display()
+        )))
+        .stdout(predicate::str::contains("web failure"));
+}
+
+#[test]
+fn test_rotated_files_read_oldest_first() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("app.log"), "2024-01-03 10:00:00 ERROR newest\n").unwrap();
+    std::fs::write(dir.path().join("app.log.1"), "2024-01-02 10:00:00 ERROR middle\n").unwrap();
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(b"2024-01-01 10:00:00 ERROR oldest\n").unwrap();
+    std::fs::write(dir.path().join("app.log.2.gz"), encoder.finish().unwrap()).unwrap();
+    std::fs::write(dir.path().join("app-20231231.log"), "2023-12-31 10:00:00 ERROR dated\n").unwrap();
+    std::fs::write(dir.path().join("other.log"), "2024-01-01 10:00:00 ERROR unrelated\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(dir.path().join("app.log"))
+        .arg("--rotated")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match("(?s)dated.*oldest.*middle.*newest").unwrap())
+        .stdout(predicate::str::contains("unrelated").not());
+}
+
+#[test]
+fn test_rotated_files_with_dot_dated_names() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("app.log"), "2024-01-03 10:00:00 ERROR today\n").unwrap();
+    std::fs::write(dir.path().join("app.log.20240102"), "2024-01-02 10:00:00 ERROR yesterday\n").unwrap();
+    std::fs::write(dir.path().join("app.log.20240101"), "2024-01-01 10:00:00 ERROR two days ago\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(dir.path().join("app.log"))
+        .arg("--rotated")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match("(?s)two days ago.*yesterday.*today").unwrap());
+}
+
+#[test]
+fn test_rotated_entry_ending_at_file_boundary_keeps_its_source() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("app.log.1"),
+        "2024-01-01 10:00:00 ERROR request failed\n  at handler.rs:42\n",
+    ).unwrap();
+    std::fs::write(dir.path().join("app.log"), "2024-01-01 10:01:00 INFO recovered\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(dir.path().join("app.log"))
+        .args(["--rotated", "--multiline", "--with-filename"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{}:2024-01-01 10:00:00 ERROR request failed",
+            dir.path().join("app.log.1").display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "{}:2024-01-01 10:01:00 INFO recovered",
+            dir.path().join("app.log").display()
+        )));
+
+    // Parsers that hold an entry until the next one starts
+    std::fs::write(
+        dir.path().join("db.log.1"),
+        "2024-01-01 10:00:00.123 UTC [42] ERROR:  relation \"users\" does not exist\n\
+         2024-01-01 10:00:00.123 UTC [42] STATEMENT:  SELECT * FROM users\n",
+    ).unwrap();
+    std::fs::write(
+        dir.path().join("db.log"),
+        "2024-01-01 10:01:00.000 UTC [43] LOG:  checkpoint complete\n",
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(dir.path().join("db.log"))
+        .args(["--rotated", "--input-format", "postgres", "--with-filename"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{}:2024-01-01 10:00:00.123 UTC [42] ERROR",
+            dir.path().join("db.log.1").display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "{}:2024-01-01 10:01:00.000 UTC [43] LOG",
+            dir.path().join("db.log").display()
+        )));
+}
+
+#[test]
+fn test_merge_interleaves_sources_by_timestamp() {
+    let mut first = NamedTempFile::new().unwrap();
+    writeln!(
+        first,
+        "2024-01-01 10:00:00 ERROR api failed\n  at handler.rs:42\n2024-01-01 10:00:02 INFO api retried"
+    ).unwrap();
+    let mut second = NamedTempFile::new().unwrap();
+    writeln!(
+        second,
+        "2024-01-01 10:00:01 ERROR db timeout\n2024-01-01 10:00:02 INFO db recovered"
+    ).unwrap();
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(first.path())
+        .arg(second.path())
+        .arg("--merge")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(
+                "(?s)api failed\n[^\n]*:  at handler.rs:42\n[^\n]*db timeout\n[^\n]*api retried\n[^\n]*db recovered",
+            )
+            .unwrap(),
+        )
+        .stdout(predicate::str::contains(format!("{}:2024-01-01 10:00:01", second.path().display())));
+}
+
+#[test]
+fn test_merge_streams_csv_and_gelf_with_source() {
+    let mut first = NamedTempFile::new().unwrap();
+    writeln!(first, "time=2024-01-01T10:00:00Z level=error msg=\"api failed\" user=alice").unwrap();
+    let mut second = NamedTempFile::new().unwrap();
+    writeln!(second, "time=2024-01-01T10:00:01Z level=info msg=\"db ok\" table=users").unwrap();
+
+    // The columns come from the first entry; later fields go to the `fields` column
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(first.path())
+        .arg(second.path())
+        .args(["--merge", "--input-format", "logfmt", "--format", "csv"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("timestamp,level,message,source,user,fields\n"))
+        .stdout(predicate::str::contains(format!("api failed,{},alice,\n", first.path().display())))
+        .stdout(predicate::str::contains(format!("db ok,{},,table=users\n", second.path().display())));
+
+    let mut cmd = Command::cargo_bin("log-parser").unwrap();
+    cmd.arg(first.path())
+        .arg(second.path())
+        .args(["--merge", "--input-format", "logfmt", "--format", "gelf"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(r#""_source":"{}""#, second.path().display())))
+        .stdout(predicate::str::contains(r#""_table":"users""#));
+}